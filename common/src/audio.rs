@@ -0,0 +1,573 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::error::ErrorKind;
+
+#[derive(Debug)]
+pub enum AudioError {
+    Io(std::io::Error),
+    Mp4(mp4ameta::Error),
+    UnsupportedFormat,
+    UnknownDuration,
+}
+
+impl std::fmt::Display for AudioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioError::Io(e) => write!(f, "Could not read the audio: {}", e),
+            AudioError::Mp4(e) => write!(f, "Invalid MP4 file: {}", e),
+            AudioError::UnsupportedFormat => {
+                write!(
+                    f,
+                    "Unsupported audio, expected a WAV, MP3, M4A, MP4 or FLAC file"
+                )
+            }
+            AudioError::UnknownDuration => write!(f, "Could not find the length of the audio"),
+        }
+    }
+}
+
+impl std::error::Error for AudioError {}
+
+impl AudioError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            AudioError::Io(_) => ErrorKind::Io,
+            _ => ErrorKind::Media,
+        }
+    }
+}
+
+/// The technical details of an audio file, read from its headers.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct AudioInfo {
+    pub duration: Duration,
+    /// Samples per second, if the file says.
+    pub sample_rate: Option<u32>,
+    /// Number of channels, if the file says.
+    pub channels: Option<u16>,
+    /// Average bits per second of the encoded audio, if it's known.
+    pub bitrate: Option<u32>,
+}
+
+/// Reads the length, sample rate, channels and bitrate of a WAV, MP3, M4A,
+/// MP4 or FLAC file.
+///
+/// The format is detected from the start of the file rather than its
+/// extension. Only headers are read, seeking past the audio between them, so
+/// no audio is decoded.
+pub fn read_audio_info(path: &Path) -> Result<AudioInfo, AudioError> {
+    let mut file = File::open(path).map_err(AudioError::Io)?;
+    let mut start = vec![];
+    file.by_ref()
+        .take(12)
+        .read_to_end(&mut start)
+        .map_err(AudioError::Io)?;
+    if start.get(4..8) == Some(b"ftyp") {
+        let tag = mp4ameta::Tag::read_from_path(path).map_err(AudioError::Mp4)?;
+        let info = tag.audio_info();
+        return Ok(AudioInfo {
+            duration: info.duration,
+            sample_rate: info.sample_rate.map(|sample_rate| sample_rate.hz()),
+            channels: info
+                .channel_config
+                .map(|channels| channels.channel_count() as u16),
+            bitrate: info.avg_bitrate.filter(|bitrate| *bitrate > 0),
+        });
+    }
+    if start.starts_with(b"RIFF") {
+        return wav_info(&mut file)
+            .map_err(AudioError::Io)?
+            .ok_or(AudioError::UnknownDuration);
+    }
+    if is_flac(&mut file).map_err(AudioError::Io)? {
+        return flac_info(&mut file)
+            .map_err(AudioError::Io)?
+            .ok_or(AudioError::UnknownDuration);
+    }
+    mp3_info(&mut file)
+        .map_err(AudioError::Io)?
+        .ok_or(AudioError::UnsupportedFormat)
+}
+
+/// Finds the length of a WAV, MP3, M4A, MP4 or FLAC file.
+pub fn audio_duration(path: &Path) -> Result<Duration, AudioError> {
+    read_audio_info(path).map(|info| info.duration)
+}
+
+/// Reads a WAV file's `fmt ` chunk and the size of its `data` chunk, seeking
+/// past the chunks in between.
+pub fn wav_info<R: Read + Seek>(reader: &mut R) -> io::Result<Option<AudioInfo>> {
+    let length = reader.seek(SeekFrom::End(0))?;
+    reader.seek(SeekFrom::Start(0))?;
+    let header = read_array::<12>(reader)?;
+    if &header[..4] != b"RIFF" || &header[8..] != b"WAVE" {
+        return Ok(None);
+    }
+    let mut position = 12;
+    let mut format = None;
+    while position + 8 <= length {
+        reader.seek(SeekFrom::Start(position))?;
+        let chunk = read_array::<8>(reader)?;
+        let size = u32::from_le_bytes(chunk[4..].try_into().expect("Slice is 4 bytes")) as u64;
+        let body = position + 8;
+        match &chunk[..4] {
+            b"fmt " => {
+                let chunk = read_array::<16>(reader)?;
+                let channels = u16::from_le_bytes([chunk[2], chunk[3]]);
+                let sample_rate = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+                let byte_rate = u32::from_le_bytes([chunk[8], chunk[9], chunk[10], chunk[11]]);
+                format = Some((channels, sample_rate, byte_rate));
+            }
+            b"data" => {
+                let Some((channels, sample_rate, byte_rate)) = format else {
+                    return Ok(None);
+                };
+                if byte_rate == 0 {
+                    return Ok(None);
+                }
+                // Recorders that stop early can leave the size unset, so it's
+                // capped to what's actually in the file.
+                let size = size.min(length - body) as u128;
+                return Ok(Some(AudioInfo {
+                    duration: Duration::from_nanos(
+                        (size * 1_000_000_000 / byte_rate as u128) as u64,
+                    ),
+                    sample_rate: Some(sample_rate),
+                    channels: Some(channels),
+                    bitrate: Some(byte_rate * 8),
+                }));
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even length.
+        position = body + size + size % 2;
+    }
+    Ok(None)
+}
+
+/// Whether the audio starts with FLAC's `fLaC` marker, after any ID3v2 tag.
+fn is_flac<R: Read + Seek>(reader: &mut R) -> io::Result<bool> {
+    let start = id3v2_tag_length(reader)?;
+    reader.seek(SeekFrom::Start(start))?;
+    let mut marker = vec![];
+    reader.take(4).read_to_end(&mut marker)?;
+    Ok(marker == b"fLaC")
+}
+
+/// Reads a FLAC file's `STREAMINFO` block, which holds the total number of
+/// samples.
+pub fn flac_info<R: Read + Seek>(reader: &mut R) -> io::Result<Option<AudioInfo>> {
+    let length = reader.seek(SeekFrom::End(0))?;
+    if !is_flac(reader)? {
+        return Ok(None);
+    }
+    let mut position = reader.stream_position()?;
+    let mut stream_info = None;
+    loop {
+        reader.seek(SeekFrom::Start(position))?;
+        let header = read_array::<4>(reader)?;
+        let is_last = header[0] & 0x80 != 0;
+        let block_type = header[0] & 0x7F;
+        let block_length = u32::from_be_bytes([0, header[1], header[2], header[3]]) as u64;
+        if block_type == 0 {
+            let block = read_array::<18>(reader)?;
+            let sample_rate =
+                (block[10] as u32) << 12 | (block[11] as u32) << 4 | (block[12] as u32) >> 4;
+            let channels = ((block[12] >> 1) & 0x07) as u16 + 1;
+            let total_samples = (block[13] as u64 & 0x0F) << 32
+                | u32::from_be_bytes([block[14], block[15], block[16], block[17]]) as u64;
+            stream_info = Some((sample_rate, channels, total_samples));
+        }
+        position += 4 + block_length;
+        if is_last {
+            break;
+        }
+    }
+
+    let Some((sample_rate, channels, total_samples)) = stream_info else {
+        return Ok(None);
+    };
+    if sample_rate == 0 || total_samples == 0 {
+        return Ok(None);
+    }
+    let duration =
+        Duration::from_nanos((total_samples as u128 * 1_000_000_000 / sample_rate as u128) as u64);
+    Ok(Some(AudioInfo {
+        duration,
+        sample_rate: Some(sample_rate),
+        channels: Some(channels),
+        bitrate: average_bitrate(length.saturating_sub(position), duration),
+    }))
+}
+
+/// Reads an MP3's length from its Xing or VBRI header, which encoders write
+/// into the first frame, or by adding up the samples in every frame if it
+/// has neither.
+///
+/// The audio must start with a frame, right after any ID3v2 tag, that is
+/// followed by a second frame where the first says it ends. Otherwise it
+/// isn't taken to be MP3 and `None` is returned.
+pub fn mp3_info<R: Read + Seek>(reader: &mut R) -> io::Result<Option<AudioInfo>> {
+    let length = reader.seek(SeekFrom::End(0))?;
+    let start = id3v2_tag_length(reader)?;
+    let Some(header) = read_frame_header(reader, start, length)? else {
+        return Ok(None);
+    };
+    if read_frame_header(reader, start + header.length as u64, length)?.is_none() {
+        return Ok(None);
+    }
+    let audio_length = length - start - id3v1_tag_length(reader)?;
+
+    reader.seek(SeekFrom::Start(start))?;
+    let mut frame = vec![];
+    reader
+        .by_ref()
+        .take(header.length as u64)
+        .read_to_end(&mut frame)?;
+    let (duration, bitrate) = match header.vbr_header(&frame) {
+        Some((frames, audio_bytes)) => {
+            let duration = Duration::from_nanos(
+                (frames as u128 * header.samples as u128 * 1_000_000_000
+                    / header.sample_rate as u128) as u64,
+            );
+            let audio_bytes = audio_bytes.map_or(audio_length, |bytes| bytes as u64);
+            (duration, average_bitrate(audio_bytes, duration))
+        }
+        None => {
+            let Some(duration) = mp3_duration(reader)? else {
+                return Ok(None);
+            };
+            (duration, average_bitrate(audio_length, duration))
+        }
+    };
+    Ok(Some(AudioInfo {
+        duration,
+        sample_rate: Some(header.sample_rate),
+        channels: Some(header.channels),
+        bitrate,
+    }))
+}
+
+fn average_bitrate(audio_bytes: u64, duration: Duration) -> Option<u32> {
+    (!duration.is_zero())
+        .then(|| (audio_bytes as f64 * 8.0 / duration.as_secs_f64()).round() as u32)
+}
+
+/// Finds the length of MP3 audio by adding up the samples in every frame.
+///
+/// Counting frames works for both constant and variable bitrate files. Only
+/// each frame's header is read before seeking to the next. Any ID3v2 tag at
+/// the start and ID3v1 tag at the end are skipped. Returns `None` if no MPEG
+/// audio frames are found.
+pub fn mp3_duration<R: Read + Seek>(reader: &mut R) -> io::Result<Option<Duration>> {
+    let end = reader.seek(SeekFrom::End(0))? - id3v1_tag_length(reader)?;
+    let mut position = id3v2_tag_length(reader)?;
+    let mut nanos: u128 = 0;
+    let mut frame_count = 0;
+
+    while position + 4 <= end {
+        match read_frame_header(reader, position, end)? {
+            Some(header) => {
+                nanos += header.samples as u128 * 1_000_000_000 / header.sample_rate as u128;
+                frame_count += 1;
+                position += header.length as u64;
+            }
+            None => position += 1,
+        }
+    }
+
+    Ok((frame_count > 0).then(|| Duration::from_nanos(nanos as u64)))
+}
+
+/// The frame header at `position`, if there is one before `end`.
+fn read_frame_header<R: Read + Seek>(
+    reader: &mut R,
+    position: u64,
+    end: u64,
+) -> io::Result<Option<Mp3FrameHeader>> {
+    if position + 4 > end {
+        return Ok(None);
+    }
+    reader.seek(SeekFrom::Start(position))?;
+    Ok(Mp3FrameHeader::parse(&read_array::<4>(reader)?))
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// The length of the ID3v2 tag at the start of the audio, or `0` if there
+/// isn't one.
+fn id3v2_tag_length<R: Read + Seek>(reader: &mut R) -> io::Result<u64> {
+    reader.seek(SeekFrom::Start(0))?;
+    let mut header = vec![];
+    reader.take(10).read_to_end(&mut header)?;
+    if header.len() < 10 || !header.starts_with(b"ID3") {
+        return Ok(0);
+    }
+    let size = header[6..10]
+        .iter()
+        .fold(0, |size, byte| (size << 7) | (*byte as u64 & 0x7F));
+    let footer = if header[5] & 0x10 != 0 { 10 } else { 0 };
+    Ok(10 + size + footer)
+}
+
+/// The length of the ID3v1 tag at the end of the audio, or `0` if there
+/// isn't one.
+fn id3v1_tag_length<R: Read + Seek>(reader: &mut R) -> io::Result<u64> {
+    if reader.seek(SeekFrom::End(0))? < 128 {
+        return Ok(0);
+    }
+    reader.seek(SeekFrom::End(-128))?;
+    Ok(if &read_array::<3>(reader)? == b"TAG" {
+        128
+    } else {
+        0
+    })
+}
+
+struct Mp3FrameHeader {
+    /// 0 is MPEG 2.5, 2 is MPEG 2 and 3 is MPEG 1.
+    version: u8,
+    sample_rate: u32,
+    samples: u32,
+    channels: u16,
+    length: usize,
+}
+
+impl Mp3FrameHeader {
+    fn parse(header: &[u8]) -> Option<Mp3FrameHeader> {
+        if header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+            return None;
+        }
+        // 0 is MPEG 2.5, 2 is MPEG 2 and 3 is MPEG 1.
+        let version = (header[1] >> 3) & 0x03;
+        // 1 is layer III, 2 is layer II and 3 is layer I.
+        let layer = (header[1] >> 1) & 0x03;
+        let bitrate_index = (header[2] >> 4) as usize;
+        let sample_rate_index = ((header[2] >> 2) & 0x03) as usize;
+        let padding = ((header[2] >> 1) & 0x01) as usize;
+        // 3 is mono, and everything else is some kind of stereo.
+        let channels = if header[3] >> 6 == 3 { 1 } else { 2 };
+        if version == 1 || layer == 0 || bitrate_index == 0 || bitrate_index == 15 {
+            return None;
+        }
+
+        let bitrates: [u32; 15] = match (version, layer) {
+            (3, 3) => [
+                0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+            ],
+            (3, 2) => [
+                0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+            ],
+            (3, 1) => [
+                0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+            ],
+            (_, 3) => [
+                0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+            ],
+            _ => [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+        };
+        let sample_rates: [u32; 3] = match version {
+            3 => [44100, 48000, 32000],
+            2 => [22050, 24000, 16000],
+            _ => [11025, 12000, 8000],
+        };
+        let bitrate = bitrates[bitrate_index] * 1000;
+        let sample_rate = *sample_rates.get(sample_rate_index)?;
+        let samples = match (version, layer) {
+            (_, 3) => 384,
+            (3, _) | (_, 2) => 1152,
+            _ => 576,
+        };
+
+        let length = if layer == 3 {
+            (12 * bitrate / sample_rate) as usize * 4 + padding * 4
+        } else {
+            (samples / 8 * bitrate / sample_rate) as usize + padding
+        };
+        Some(Mp3FrameHeader {
+            version,
+            sample_rate,
+            samples,
+            channels,
+            length,
+        })
+    }
+
+    /// Reads the frame count and, if given, the audio byte count from a Xing
+    /// (or LAME `Info`) or VBRI header in `frame`, the first frame of the file.
+    fn vbr_header(&self, frame: &[u8]) -> Option<(u32, Option<u32>)> {
+        let read_u32 = |offset: usize| {
+            frame
+                .get(offset..offset + 4)
+                .map(|bytes| u32::from_be_bytes(bytes.try_into().expect("Slice is 4 bytes")))
+        };
+
+        // The Xing header follows the side information, whose size depends
+        // on the MPEG version and whether the audio is mono.
+        let side_info = match (self.version == 3, self.channels == 1) {
+            (true, false) => 32,
+            (true, true) | (false, false) => 17,
+            (false, true) => 9,
+        };
+        let xing = 4 + side_info;
+        if matches!(frame.get(xing..xing + 4), Some(b"Xing" | b"Info")) {
+            let flags = read_u32(xing + 4)?;
+            if flags & 0x01 == 0 {
+                return None;
+            }
+            let frames = read_u32(xing + 8)?;
+            let bytes = if flags & 0x02 != 0 {
+                read_u32(xing + 12)
+            } else {
+                None
+            };
+            return Some((frames, bytes));
+        }
+
+        // VBRI headers always start 32 bytes after the frame header.
+        if frame.get(36..40) == Some(b"VBRI") {
+            return Some((read_u32(50)?, Some(read_u32(46)?)));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// MPEG 1 layer III frames at 128 kbps and 44.1 kHz, each 417 bytes long.
+    fn mp3_frames(count: usize) -> Vec<u8> {
+        let mut frame = vec![0; 417];
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+        frame.repeat(count)
+    }
+
+    fn mp3_duration_of(bytes: &[u8]) -> Option<Duration> {
+        mp3_duration(&mut Cursor::new(bytes)).expect("Reading from memory should succeed")
+    }
+
+    fn mp3_info_of(bytes: &[u8]) -> Option<AudioInfo> {
+        mp3_info(&mut Cursor::new(bytes)).expect("Reading from memory should succeed")
+    }
+
+    #[test]
+    fn adds_up_frame_durations() {
+        let duration = mp3_duration_of(&mp3_frames(100)).expect("mp3_duration should succeed");
+
+        assert_eq!(duration.as_millis(), 100 * 1152 * 1000 / 44100);
+    }
+
+    #[test]
+    fn skips_id3_tags() {
+        let mut bytes = b"ID3\x04\x00\x00\x00\x00\x00\x05".to_vec();
+        bytes.extend([0xFF; 5]);
+        bytes.extend(mp3_frames(10));
+        bytes.extend(b"TAG");
+        bytes.extend([0; 125]);
+
+        assert_eq!(mp3_duration_of(&bytes), mp3_duration_of(&mp3_frames(10)),);
+        assert_eq!(mp3_duration_of(b"not audio"), None);
+        assert_eq!(
+            mp3_info_of(&bytes).map(|info| info.duration),
+            mp3_duration_of(&bytes)
+        );
+    }
+
+    #[test]
+    fn reads_xing_header() {
+        let mut bytes = mp3_frames(3);
+        bytes[36..40].copy_from_slice(b"Xing");
+        bytes[40..44].copy_from_slice(&3u32.to_be_bytes());
+        bytes[44..48].copy_from_slice(&1000u32.to_be_bytes());
+        bytes[48..52].copy_from_slice(&417_000u32.to_be_bytes());
+
+        let info = mp3_info_of(&bytes).expect("mp3_info should succeed");
+
+        assert_eq!(info.duration.as_millis(), 1000 * 1152 * 1000 / 44100);
+        assert_eq!(info.sample_rate, Some(44100));
+        assert_eq!(info.channels, Some(2));
+        assert_eq!(info.bitrate, Some(127_706));
+    }
+
+    #[test]
+    fn needs_mp3_frames_at_the_start() {
+        let mut late_frames = b"not audio".to_vec();
+        late_frames.extend(mp3_frames(10));
+        let mut lone_frame = mp3_frames(1);
+        lone_frame.extend([0; 1000]);
+
+        assert_eq!(mp3_info_of(&late_frames), None);
+        assert_eq!(mp3_info_of(&lone_frame), None);
+        assert!(mp3_info_of(&mp3_frames(2)).is_some());
+    }
+
+    #[test]
+    fn rejects_unknown_audio() {
+        let path = std::env::temp_dir().join(format!("audio_unknown_{}.txt", std::process::id()));
+        let mut bytes = b"Some notes about the episode".to_vec();
+        bytes.extend(mp3_frames(10));
+        std::fs::write(&path, &bytes).unwrap();
+
+        let result = read_audio_info(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(AudioError::UnsupportedFormat)));
+    }
+
+    #[test]
+    fn reads_wav_header() {
+        let mut bytes = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+        bytes.extend(16u32.to_le_bytes());
+        bytes.extend([1, 0, 2, 0]);
+        bytes.extend(48_000u32.to_le_bytes());
+        bytes.extend(192_000u32.to_le_bytes());
+        bytes.extend([4, 0, 16, 0]);
+        bytes.extend(b"LIST\x03\0\0\0abc\0");
+        bytes.extend(b"data");
+        bytes.extend(96_000u32.to_le_bytes());
+        bytes.extend(vec![0; 96_000]);
+        let path = std::env::temp_dir().join(format!("audio_info_{}.wav", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+
+        let info = read_audio_info(&path).expect("read_audio_info should succeed");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            info,
+            AudioInfo {
+                duration: Duration::from_millis(500),
+                sample_rate: Some(48_000),
+                channels: Some(2),
+                bitrate: Some(1_536_000),
+            }
+        );
+    }
+
+    #[test]
+    fn reads_flac_stream_info() {
+        let mut stream_info = vec![0; 34];
+        // 44.1 kHz, 2 channels, 16 bits per sample and 441,000 samples.
+        stream_info[10..18].copy_from_slice(&[0x0A, 0xC4, 0x42, 0xF0, 0x00, 0x06, 0xBA, 0xA8]);
+        let mut bytes = b"fLaC\x80\x00\x00\x22".to_vec();
+        bytes.extend(stream_info);
+        bytes.extend(vec![0; 1000]);
+
+        let info = flac_info(&mut Cursor::new(bytes))
+            .expect("Reading from memory should succeed")
+            .expect("flac_info should succeed");
+
+        assert_eq!(info.duration, Duration::from_secs(10));
+        assert_eq!(info.sample_rate, Some(44_100));
+        assert_eq!(info.channels, Some(2));
+        assert_eq!(info.bitrate, Some(800));
+    }
+}
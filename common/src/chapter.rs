@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use crate::error::ErrorKind;
+use crate::types::{OutlineEntry, TimeCode};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ChapterError {
+    ChapterPastEnd {
+        text: String,
+        start: TimeCode,
+        end: TimeCode,
+    },
+}
+
+impl std::fmt::Display for ChapterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChapterError::ChapterPastEnd { text, start, end } => write!(
+                f,
+                "Chapter '{}' at {} starts after the episode ends at {}",
+                text, start, end
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChapterError {}
+
+impl ChapterError {
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::Chapters
+    }
+}
+
+/// The time code the episode ends at, or an error for the first entry that
+/// starts at or after it.
+pub fn episode_end(
+    entries: &[OutlineEntry],
+    episode_duration: Duration,
+) -> Result<TimeCode, ChapterError> {
+    let end = TimeCode::from_duration(episode_duration).unwrap_or_default();
+    match entries.iter().find(|entry| entry.time_code >= end) {
+        Some(entry) => Err(ChapterError::ChapterPastEnd {
+            text: entry.text.clone(),
+            start: entry.time_code,
+            end,
+        }),
+        None => Ok(end),
+    }
+}
+
+/// An outline entry with where it ends and how long it lasts.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Chapter {
+    pub entry: OutlineEntry,
+    pub start: TimeCode,
+    /// Where the next entry at the same or a higher level starts, so a chapter
+    /// includes its sub-topics, or the end of the episode for the last one.
+    pub end: TimeCode,
+    pub duration: Duration,
+}
+
+impl Chapter {
+    /// Works out where every entry of an outline ends, in time order, given
+    /// how long the episode is.
+    ///
+    /// Fails if an entry starts at or after the end of the episode.
+    pub fn from_outline(
+        entries: &[OutlineEntry],
+        episode_duration: Duration,
+    ) -> Result<Vec<Chapter>, ChapterError> {
+        let mut entries = entries.to_vec();
+        entries.sort();
+        let episode_end = episode_end(&entries, episode_duration)?;
+
+        Ok(entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let end = entries[index + 1..]
+                    .iter()
+                    .find(|later| later.level <= entry.level)
+                    .map_or(episode_end, |later| later.time_code);
+                Chapter {
+                    entry: entry.clone(),
+                    start: entry.time_code,
+                    end,
+                    duration: end.saturating_duration_since(&entry.time_code),
+                }
+            })
+            .collect())
+    }
+
+    /// The length of the chapter for people to read, like `12 min`, or
+    /// `45 sec` for chapters under a minute.
+    pub fn length_label(&self) -> String {
+        let seconds = self.duration.as_secs_f64();
+        if seconds < 59.5 {
+            format!("{} sec", seconds.round() as u64)
+        } else {
+            format!("{} min", (seconds / 60.0).round() as u64)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_outline;
+
+    #[test]
+    fn ends_chapters_at_the_next_chapter_or_episode_end() {
+        let entries = parse_outline("1:10 Robots\n0:00 Start\n  1:30 First robot\n12:40.5 Links")
+            .expect("parse_outline should succeed");
+
+        let chapters = Chapter::from_outline(&entries, Duration::from_secs(13 * 60))
+            .expect("Chapter::from_outline should succeed");
+
+        assert_eq!(
+            chapters
+                .iter()
+                .map(|chapter| format!(
+                    "{} {}-{} {}",
+                    chapter.entry.text,
+                    chapter.start,
+                    chapter.end,
+                    chapter.length_label()
+                ))
+                .collect::<Vec<String>>(),
+            vec![
+                "Start 0:00-1:10 1 min",
+                "Robots 1:10-12:40.500 12 min",
+                "First robot 1:30-12:40.500 11 min",
+                "Links 12:40.500-13:00 20 sec",
+            ]
+        );
+        assert_eq!(chapters[3].duration, Duration::from_millis(19_500));
+    }
+
+    #[test]
+    fn rejects_chapters_after_the_episode() {
+        let entries = parse_outline("0:00 Start\n10:00 Too late").unwrap();
+
+        assert_eq!(
+            Chapter::from_outline(&entries, Duration::from_secs(600)),
+            Err(ChapterError::ChapterPastEnd {
+                text: "Too late".to_string(),
+                start: TimeCode::new(0, 10, 0).unwrap(),
+                end: TimeCode::new(0, 10, 0).unwrap(),
+            })
+        );
+    }
+}
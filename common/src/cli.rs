@@ -0,0 +1,88 @@
+use clap::{arg, value_parser, Arg, ArgMatches};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::audio::{audio_duration, AudioError};
+use crate::outline_source::{OutlineFormat, OutlineOptions};
+use crate::parse_fcpxml::MarkerFilter;
+use crate::types::{FrameRate, TimeCode};
+
+/// The `--duration` argument, for the length of the episode.
+pub fn duration_arg(help: &'static str) -> Arg {
+    arg!(--duration <time>)
+        .help(help)
+        .value_parser(|text: &str| text.parse::<TimeCode>().map_err(|e| e.to_string()))
+}
+
+/// The `--audio_path` argument, for an episode's audio to read its length
+/// from instead of giving `--duration`.
+pub fn audio_path_arg() -> Arg {
+    arg!(
+        --audio_path <file> "Episode WAV, MP3, M4A or FLAC to read the length of the episode from"
+    )
+    .conflicts_with("duration")
+    .value_parser(value_parser!(PathBuf))
+}
+
+/// The length of the episode from `--duration`, or read from the audio given
+/// with `--audio_path`, or nothing if neither was given.
+///
+/// `audio_error` turns a failure to read the audio into the caller's error.
+pub fn get_episode_duration<E>(
+    matches: &ArgMatches,
+    audio_error: impl FnOnce(PathBuf, AudioError) -> E,
+) -> Result<Option<Duration>, E> {
+    if let Some(duration) = matches.get_one::<TimeCode>("duration") {
+        return Ok(Some(duration.to_duration()));
+    }
+    match matches.get_one::<PathBuf>("audio_path") {
+        Some(audio_path) => audio_duration(audio_path)
+            .map(Some)
+            .map_err(|e| audio_error(audio_path.clone(), e)),
+        None => Ok(None),
+    }
+}
+
+/// The `--outline_format` argument, for reading an outline in a format other
+/// than the one detected from the file.
+pub fn outline_format_arg() -> Arg {
+    arg!(
+        --outline_format <format> "Format of the outline, detected from the file if not given: text, yaml, fcpxml, resolve, premiere, audacity or reaper"
+    )
+    .value_parser(value_parser!(OutlineFormat))
+}
+
+/// The `--frame_rate` argument, for SMPTE time codes in the outline.
+pub fn frame_rate_arg() -> Arg {
+    arg!(
+        --frame_rate <rate> "Frame rate of SMPTE time codes in the outline, e.g. 25 or 29.97df"
+    )
+    .default_value("30")
+    .value_parser(value_parser!(FrameRate))
+}
+
+/// The `--fcpxml_markers` argument, for which Final Cut Pro markers are
+/// chapters.
+pub fn fcpxml_markers_arg() -> Arg {
+    arg!(
+        --fcpxml_markers <kinds> "Comma separated Final Cut Pro marker kinds to use from an FCPXML outline: chapter, standard and todo"
+    )
+    .default_value("chapter,standard")
+    .value_parser(value_parser!(MarkerFilter))
+}
+
+/// How to read the outline, from the arguments added with
+/// `outline_format_arg`, `frame_rate_arg` and `fcpxml_markers_arg`.
+pub fn get_outline_options(matches: &ArgMatches) -> OutlineOptions {
+    let frame_rate: &FrameRate = matches
+        .get_one("frame_rate")
+        .expect("A frame rate was provided");
+    let fcpxml_markers: &MarkerFilter = matches
+        .get_one("fcpxml_markers")
+        .expect("FCPXML marker kinds were provided");
+    OutlineOptions {
+        format: matches.get_one::<OutlineFormat>("outline_format").copied(),
+        frame_rate: *frame_rate,
+        fcpxml_markers: *fcpxml_markers,
+    }
+}
@@ -0,0 +1,162 @@
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+
+/// What kind of problem stopped a command.
+///
+/// Each kind exits with its own code so scripts can tell a missing file from
+/// a broken outline. Clap already exits with 2 for bad arguments, and 1 is
+/// left for everything else.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ErrorKind {
+    /// A file couldn't be read or written.
+    Io,
+    /// A podcast or episode file isn't valid YAML or is missing fields.
+    Config,
+    /// The outline couldn't be parsed.
+    Outline,
+    /// The outline breaks the chapter rules, or a chapter starts after the
+    /// episode ends.
+    Chapters,
+    /// An audio or video file couldn't be read or have chapters written to it.
+    Media,
+    /// A template failed to render.
+    Template,
+    /// The transcript couldn't be read or marked.
+    Transcript,
+}
+
+impl ErrorKind {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorKind::Io => 3,
+            ErrorKind::Config => 4,
+            ErrorKind::Outline => 5,
+            ErrorKind::Chapters => 6,
+            ErrorKind::Media => 7,
+            ErrorKind::Template => 8,
+            ErrorKind::Transcript => 9,
+        }
+    }
+}
+
+/// A problem reading or writing a file, with the path it happened on.
+#[derive(Debug)]
+pub enum FileError {
+    Read(PathBuf, std::io::Error),
+    Write(PathBuf, std::io::Error),
+    Yaml {
+        path: PathBuf,
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+}
+
+impl FileError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            FileError::Read(..) | FileError::Write(..) => ErrorKind::Io,
+            FileError::Yaml { .. } => ErrorKind::Config,
+        }
+    }
+}
+
+impl std::fmt::Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileError::Read(path, e) => write!(f, "Could not read {}: {}", path.display(), e),
+            FileError::Write(path, e) => write!(f, "Could not write {}: {}", path.display(), e),
+            FileError::Yaml {
+                path,
+                line: Some(line),
+                column: Some(column),
+                message,
+            } => write!(f, "{}:{}:{}: {}", path.display(), line, column, message),
+            FileError::Yaml { path, message, .. } => write!(f, "{}: {}", path.display(), message),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+/// Reads a UTF-8 file, keeping its path in the error.
+pub fn read_file(path: &Path) -> Result<String, FileError> {
+    std::fs::read_to_string(path).map_err(|e| FileError::Read(path.to_path_buf(), e))
+}
+
+/// Writes a file, keeping its path in the error.
+pub fn write_file(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), FileError> {
+    std::fs::write(path, contents).map_err(|e| FileError::Write(path.to_path_buf(), e))
+}
+
+/// Reads a YAML file into `T`, reporting where in the file it's invalid.
+pub fn read_yaml<T: DeserializeOwned>(path: &Path) -> Result<T, FileError> {
+    serde_yaml::from_str(&read_file(path)?).map_err(|e| yaml_error(path, &e))
+}
+
+fn yaml_error(path: &Path, error: &serde_yaml::Error) -> FileError {
+    let message = error.to_string();
+    let location = error.location();
+    // serde_yaml ends its messages with the location, which is shown before
+    // the message instead.
+    let message = location
+        .as_ref()
+        .and_then(|location| {
+            message.strip_suffix(&format!(
+                " at line {} column {}",
+                location.line(),
+                location.column()
+            ))
+        })
+        .unwrap_or(&message)
+        .to_string();
+    FileError::Yaml {
+        path: path.to_path_buf(),
+        line: location.as_ref().map(|location| location.line()),
+        column: location.as_ref().map(|location| location.column()),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)]
+    struct Podcast {
+        name: String,
+        links: Vec<String>,
+    }
+
+    #[test]
+    fn reports_where_yaml_is_invalid() {
+        let path = std::env::temp_dir().join("error_invalid_podcast.yaml");
+        std::fs::write(&path, "name: Robots\nlinks: 3\n").unwrap();
+
+        let error = read_yaml::<Podcast>(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(error.kind(), ErrorKind::Config);
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "{}:2:8: links: invalid type: integer `3`, expected a sequence",
+                path.display()
+            )
+        );
+    }
+
+    #[test]
+    fn keeps_the_path_of_missing_files() {
+        let path = Path::new("no/such/podcast.yaml");
+
+        let error = read_yaml::<Podcast>(path).unwrap_err();
+
+        assert_eq!(error.kind(), ErrorKind::Io);
+        assert!(error
+            .to_string()
+            .starts_with("Could not read no/such/podcast.yaml: "));
+    }
+}
@@ -0,0 +1,620 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::ErrorKind;
+use crate::types::OutlineEntry;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ExportChaptersError {
+    /// The format needs the last chapter's end, which is only known from the
+    /// total duration.
+    MissingDuration(ChapterFormat),
+    ChapterPastEnd {
+        text: String,
+        duration: Duration,
+    },
+}
+
+impl std::fmt::Display for ExportChaptersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportChaptersError::MissingDuration(format) => write!(
+                f,
+                "{} chapters need the total duration to end the last chapter",
+                format.name()
+            ),
+            ExportChaptersError::ChapterPastEnd { text, duration } => write!(
+                f,
+                "Chapter '{}' starts after the end at {}",
+                text,
+                format_clock(*duration, '.', 3)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExportChaptersError {}
+
+impl ExportChaptersError {
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::Chapters
+    }
+}
+
+/// Writes an outline as chapters in a text format that another tool reads.
+pub trait ChapterExporter {
+    /// Writes the chapters of `entries`, where `duration` is the length of the
+    /// media they belong to, if it's known.
+    fn export(
+        &self,
+        entries: &[OutlineEntry],
+        duration: Option<Duration>,
+    ) -> Result<String, ExportChaptersError>;
+}
+
+/// The chapter formats that can be exported.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ChapterFormat {
+    WebVtt,
+    FfMetadata,
+    MatroskaXml,
+    Cue,
+    Ogm,
+}
+
+impl ChapterFormat {
+    pub const ALL: [ChapterFormat; 5] = [
+        ChapterFormat::WebVtt,
+        ChapterFormat::FfMetadata,
+        ChapterFormat::MatroskaXml,
+        ChapterFormat::Cue,
+        ChapterFormat::Ogm,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChapterFormat::WebVtt => "WebVTT",
+            ChapterFormat::FfMetadata => "FFmetadata",
+            ChapterFormat::MatroskaXml => "Matroska XML",
+            ChapterFormat::Cue => "CUE",
+            ChapterFormat::Ogm => "OGM",
+        }
+    }
+
+    /// The usual extension of files in this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ChapterFormat::WebVtt => "vtt",
+            ChapterFormat::FfMetadata => "ffmetadata",
+            ChapterFormat::MatroskaXml => "xml",
+            ChapterFormat::Cue => "cue",
+            ChapterFormat::Ogm => "txt",
+        }
+    }
+
+    /// The exporter for this format, using its default settings.
+    pub fn exporter(&self) -> Box<dyn ChapterExporter> {
+        match self {
+            ChapterFormat::WebVtt => Box::new(WebVtt),
+            ChapterFormat::FfMetadata => Box::new(FfMetadata),
+            ChapterFormat::MatroskaXml => Box::new(MatroskaXml::default()),
+            ChapterFormat::Cue => Box::new(Cue::default()),
+            ChapterFormat::Ogm => Box::new(Ogm),
+        }
+    }
+}
+
+impl FromStr for ChapterFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<ChapterFormat, Self::Err> {
+        match text.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "webvtt" | "vtt" => Ok(ChapterFormat::WebVtt),
+            "ffmetadata" | "ffmetadata1" | "ffmpeg" => Ok(ChapterFormat::FfMetadata),
+            "matroska" | "matroskaxml" | "mkv" | "xml" => Ok(ChapterFormat::MatroskaXml),
+            "cue" => Ok(ChapterFormat::Cue),
+            "ogm" => Ok(ChapterFormat::Ogm),
+            _ => Err(format!(
+                "Unknown chapter format '{}', expected webvtt, ffmetadata, matroska, cue or ogm",
+                text
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for ChapterFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ChapterFormat::WebVtt => "webvtt",
+            ChapterFormat::FfMetadata => "ffmetadata",
+            ChapterFormat::MatroskaXml => "matroska",
+            ChapterFormat::Cue => "cue",
+            ChapterFormat::Ogm => "ogm",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A chapter with its place on the timeline worked out.
+struct TimedChapter<'a> {
+    entry: &'a OutlineEntry,
+    start: Duration,
+    /// Where the next chapter at the same or a higher level starts, or the
+    /// total duration for the last one.
+    end: Option<Duration>,
+    /// Where the next chapter of any level starts, or the total duration.
+    next: Option<Duration>,
+}
+
+/// Sorts the entries and finds where each one ends.
+///
+/// Formats that can't keep a chapter out of the table of contents leave
+/// hidden entries out with `include_hidden`, so the chapter before a hidden
+/// one runs on until the next one that is shown.
+fn timed_chapters(
+    entries: &[OutlineEntry],
+    duration: Option<Duration>,
+    include_hidden: bool,
+) -> Result<Vec<TimedChapter<'_>>, ExportChaptersError> {
+    let mut entries = entries
+        .iter()
+        .filter(|entry| include_hidden || !entry.hidden)
+        .collect::<Vec<&OutlineEntry>>();
+    entries.sort();
+    if let (Some(duration), Some(last)) = (duration, entries.last()) {
+        if last.time_code.to_duration() >= duration {
+            return Err(ExportChaptersError::ChapterPastEnd {
+                text: last.text.clone(),
+                duration,
+            });
+        }
+    }
+
+    Ok(entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let later = &entries[index + 1..];
+            TimedChapter {
+                entry,
+                start: entry.time_code.to_duration(),
+                end: later
+                    .iter()
+                    .find(|later| later.level <= entry.level)
+                    .map(|later| later.time_code.to_duration())
+                    .or(duration),
+                next: later
+                    .first()
+                    .map(|later| later.time_code.to_duration())
+                    .or(duration),
+            }
+        })
+        .collect())
+}
+
+/// Formats a duration as `HH:MM:SS` followed by `precision` digits of the
+/// fraction after `separator`.
+fn format_clock(duration: Duration, separator: char, precision: u32) -> String {
+    let seconds = duration.as_secs();
+    let fraction = duration.subsec_nanos() / 10u32.pow(9 - precision);
+    format!(
+        "{:02}:{:02}:{:02}{}{:0width$}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        separator,
+        fraction,
+        width = precision as usize
+    )
+}
+
+/// A WebVTT chapters track, like the ones read by `<track kind="chapters">`.
+pub struct WebVtt;
+
+impl ChapterExporter for WebVtt {
+    fn export(
+        &self,
+        entries: &[OutlineEntry],
+        duration: Option<Duration>,
+    ) -> Result<String, ExportChaptersError> {
+        let mut output = "WEBVTT\n".to_string();
+        for (index, chapter) in timed_chapters(entries, duration, false)?.iter().enumerate() {
+            let end = chapter
+                .next
+                .ok_or(ExportChaptersError::MissingDuration(ChapterFormat::WebVtt))?;
+            output += &format!(
+                "\n{}\n{} --> {}\n{}\n",
+                index + 1,
+                format_clock(chapter.start, '.', 3),
+                format_clock(end, '.', 3),
+                chapter.entry.text.replace("-->", "->")
+            );
+        }
+        Ok(output)
+    }
+}
+
+/// An ffmpeg metadata file, for muxing chapters in with
+/// `ffmpeg -i input -i chapters.ffmetadata -map_chapters 1`.
+pub struct FfMetadata;
+
+impl ChapterExporter for FfMetadata {
+    fn export(
+        &self,
+        entries: &[OutlineEntry],
+        duration: Option<Duration>,
+    ) -> Result<String, ExportChaptersError> {
+        let mut output = ";FFMETADATA1\n".to_string();
+        for chapter in timed_chapters(entries, duration, false)? {
+            let end = chapter.next.ok_or(ExportChaptersError::MissingDuration(
+                ChapterFormat::FfMetadata,
+            ))?;
+            output += &format!(
+                "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+                chapter.start.as_millis(),
+                end.as_millis(),
+                escape_ffmetadata(&chapter.entry.text)
+            );
+        }
+        Ok(output)
+    }
+}
+
+/// Escapes the characters that are special in ffmpeg metadata values.
+fn escape_ffmetadata(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// A Matroska chapters XML file, for `mkvmerge --chapters`.
+///
+/// Nested outline entries become nested chapters, and hidden entries are
+/// flagged as hidden chapters.
+pub struct MatroskaXml {
+    /// The ISO 639-2 language of the chapter titles.
+    pub language: String,
+}
+
+impl Default for MatroskaXml {
+    fn default() -> MatroskaXml {
+        MatroskaXml {
+            language: "eng".to_string(),
+        }
+    }
+}
+
+impl ChapterExporter for MatroskaXml {
+    fn export(
+        &self,
+        entries: &[OutlineEntry],
+        duration: Option<Duration>,
+    ) -> Result<String, ExportChaptersError> {
+        let mut output = concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<!DOCTYPE Chapters SYSTEM \"matroskachapters.dtd\">\n",
+            "<Chapters>\n",
+            "  <EditionEntry>\n",
+        )
+        .to_string();
+        let mut open_levels: Vec<usize> = vec![];
+        for chapter in timed_chapters(entries, duration, true)? {
+            close_chapter_atoms(&mut output, &mut open_levels, chapter.entry.level);
+            let indent = "  ".repeat(open_levels.len() + 2);
+            output += &format!("{}<ChapterAtom>\n", indent);
+            output += &format!(
+                "{}  <ChapterTimeStart>{}</ChapterTimeStart>\n",
+                indent,
+                format_clock(chapter.start, '.', 9)
+            );
+            if let Some(end) = chapter.end {
+                output += &format!(
+                    "{}  <ChapterTimeEnd>{}</ChapterTimeEnd>\n",
+                    indent,
+                    format_clock(end, '.', 9)
+                );
+            }
+            if chapter.entry.hidden {
+                output += &format!("{}  <ChapterFlagHidden>1</ChapterFlagHidden>\n", indent);
+            }
+            output += &format!("{}  <ChapterDisplay>\n", indent);
+            output += &format!(
+                "{}    <ChapterString>{}</ChapterString>\n",
+                indent,
+                escape_xml(&chapter.entry.text)
+            );
+            output += &format!(
+                "{}    <ChapterLanguage>{}</ChapterLanguage>\n",
+                indent, self.language
+            );
+            output += &format!("{}  </ChapterDisplay>\n", indent);
+            open_levels.push(chapter.entry.level);
+        }
+        close_chapter_atoms(&mut output, &mut open_levels, 0);
+        output += "  </EditionEntry>\n</Chapters>\n";
+        Ok(output)
+    }
+}
+
+/// Closes the open chapters at `level` or deeper, since the next chapter is
+/// their sibling or belongs to one of their parents.
+fn close_chapter_atoms(output: &mut String, open_levels: &mut Vec<usize>, level: usize) {
+    while open_levels.last().is_some_and(|open| *open >= level) {
+        open_levels.pop();
+        *output += &format!("{}</ChapterAtom>\n", "  ".repeat(open_levels.len() + 2));
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A CUE sheet that splits one audio file into a track per chapter.
+pub struct Cue {
+    /// The name of the audio file the sheet describes.
+    pub file_name: String,
+}
+
+impl Default for Cue {
+    fn default() -> Cue {
+        Cue {
+            file_name: "episode.wav".to_string(),
+        }
+    }
+}
+
+impl Cue {
+    /// CUE sheets count time in frames of 1/75th of a second.
+    const FRAMES_PER_SECOND: u128 = 75;
+}
+
+impl ChapterExporter for Cue {
+    fn export(
+        &self,
+        entries: &[OutlineEntry],
+        duration: Option<Duration>,
+    ) -> Result<String, ExportChaptersError> {
+        let file_type = if self.file_name.to_lowercase().ends_with(".mp3") {
+            "MP3"
+        } else {
+            "WAVE"
+        };
+        let mut output = format!(
+            "FILE \"{}\" {}\n",
+            self.file_name.replace('"', "'"),
+            file_type
+        );
+        for (index, chapter) in timed_chapters(entries, duration, false)?.iter().enumerate() {
+            let frames = chapter.start.as_millis() * Cue::FRAMES_PER_SECOND / 1000;
+            output += &format!(
+                "  TRACK {:02} AUDIO\n    TITLE \"{}\"\n    INDEX 01 {:02}:{:02}:{:02}\n",
+                index + 1,
+                chapter.entry.text.replace('"', "'"),
+                frames / Cue::FRAMES_PER_SECOND / 60,
+                frames / Cue::FRAMES_PER_SECOND % 60,
+                frames % Cue::FRAMES_PER_SECOND
+            );
+        }
+        Ok(output)
+    }
+}
+
+/// OGM style chapters, as read by mkvmerge and many media players.
+pub struct Ogm;
+
+impl ChapterExporter for Ogm {
+    fn export(
+        &self,
+        entries: &[OutlineEntry],
+        duration: Option<Duration>,
+    ) -> Result<String, ExportChaptersError> {
+        Ok(timed_chapters(entries, duration, false)?
+            .iter()
+            .enumerate()
+            .map(|(index, chapter)| {
+                format!(
+                    "CHAPTER{number:02}={}\nCHAPTER{number:02}NAME={}\n",
+                    format_clock(chapter.start, '.', 3),
+                    chapter.entry.text,
+                    number = index + 1
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_outline;
+
+    fn outline() -> Vec<OutlineEntry> {
+        parse_outline("0:00 Introduction\n1:10.5 Robots & \"Rae\"\n  2:00 First robot\n3:00 Links")
+            .expect("parse_outline should succeed")
+    }
+
+    const DURATION: Option<Duration> = Some(Duration::from_secs(4 * 60));
+
+    #[test]
+    fn exports_webvtt() {
+        assert_eq!(
+            WebVtt.export(&outline(), DURATION).unwrap(),
+            "WEBVTT
+
+1
+00:00:00.000 --> 00:01:10.500
+Introduction
+
+2
+00:01:10.500 --> 00:02:00.000
+Robots & \"Rae\"
+
+3
+00:02:00.000 --> 00:03:00.000
+First robot
+
+4
+00:03:00.000 --> 00:04:00.000
+Links
+"
+        );
+        assert_eq!(
+            WebVtt.export(&outline(), None),
+            Err(ExportChaptersError::MissingDuration(ChapterFormat::WebVtt))
+        );
+    }
+
+    #[test]
+    fn exports_ffmetadata() {
+        let entries = parse_outline("0:00 Intro; part=1\n0:30 Demo").unwrap();
+
+        assert_eq!(
+            FfMetadata
+                .export(&entries, Some(Duration::from_secs(60)))
+                .unwrap(),
+            ";FFMETADATA1
+
+[CHAPTER]
+TIMEBASE=1/1000
+START=0
+END=30000
+title=Intro\\; part\\=1
+
+[CHAPTER]
+TIMEBASE=1/1000
+START=30000
+END=60000
+title=Demo
+"
+        );
+    }
+
+    #[test]
+    fn exports_nested_matroska_xml() {
+        assert_eq!(
+            MatroskaXml::default().export(&outline(), None).unwrap(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE Chapters SYSTEM "matroskachapters.dtd">
+<Chapters>
+  <EditionEntry>
+    <ChapterAtom>
+      <ChapterTimeStart>00:00:00.000000000</ChapterTimeStart>
+      <ChapterTimeEnd>00:01:10.500000000</ChapterTimeEnd>
+      <ChapterDisplay>
+        <ChapterString>Introduction</ChapterString>
+        <ChapterLanguage>eng</ChapterLanguage>
+      </ChapterDisplay>
+    </ChapterAtom>
+    <ChapterAtom>
+      <ChapterTimeStart>00:01:10.500000000</ChapterTimeStart>
+      <ChapterTimeEnd>00:03:00.000000000</ChapterTimeEnd>
+      <ChapterDisplay>
+        <ChapterString>Robots &amp; &quot;Rae&quot;</ChapterString>
+        <ChapterLanguage>eng</ChapterLanguage>
+      </ChapterDisplay>
+      <ChapterAtom>
+        <ChapterTimeStart>00:02:00.000000000</ChapterTimeStart>
+        <ChapterTimeEnd>00:03:00.000000000</ChapterTimeEnd>
+        <ChapterDisplay>
+          <ChapterString>First robot</ChapterString>
+          <ChapterLanguage>eng</ChapterLanguage>
+        </ChapterDisplay>
+      </ChapterAtom>
+    </ChapterAtom>
+    <ChapterAtom>
+      <ChapterTimeStart>00:03:00.000000000</ChapterTimeStart>
+      <ChapterDisplay>
+        <ChapterString>Links</ChapterString>
+        <ChapterLanguage>eng</ChapterLanguage>
+      </ChapterDisplay>
+    </ChapterAtom>
+  </EditionEntry>
+</Chapters>
+"#
+        );
+    }
+
+    #[test]
+    fn exports_cue_sheet() {
+        assert_eq!(
+            Cue::default().export(&outline(), None).unwrap(),
+            r#"FILE "episode.wav" WAVE
+  TRACK 01 AUDIO
+    TITLE "Introduction"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "Robots & 'Rae'"
+    INDEX 01 01:10:37
+  TRACK 03 AUDIO
+    TITLE "First robot"
+    INDEX 01 02:00:00
+  TRACK 04 AUDIO
+    TITLE "Links"
+    INDEX 01 03:00:00
+"#
+        );
+    }
+
+    #[test]
+    fn exports_ogm() {
+        assert_eq!(
+            Ogm.export(&outline(), None).unwrap(),
+            "CHAPTER01=00:00:00.000
+CHAPTER01NAME=Introduction
+CHAPTER02=00:01:10.500
+CHAPTER02NAME=Robots & \"Rae\"
+CHAPTER03=00:02:00.000
+CHAPTER03NAME=First robot
+CHAPTER04=00:03:00.000
+CHAPTER04NAME=Links
+"
+        );
+    }
+
+    #[test]
+    fn hides_hidden_chapters() {
+        let entries = parse_outline("0:00 Intro\n0:20 Sponsor | hidden\n0:30 Demo").unwrap();
+
+        assert_eq!(
+            Ogm.export(&entries, None).unwrap(),
+            "CHAPTER01=00:00:00.000
+CHAPTER01NAME=Intro
+CHAPTER02=00:00:30.000
+CHAPTER02NAME=Demo
+"
+        );
+        assert!(MatroskaXml::default()
+            .export(&entries, None)
+            .unwrap()
+            .contains(
+                "<ChapterTimeEnd>00:00:30.000000000</ChapterTimeEnd>
+      <ChapterFlagHidden>1</ChapterFlagHidden>
+      <ChapterDisplay>
+        <ChapterString>Sponsor</ChapterString>"
+            ));
+    }
+
+    #[test]
+    fn rejects_chapters_after_the_end() {
+        assert!(matches!(
+            Ogm.export(&outline(), Some(Duration::from_secs(60))),
+            Err(ExportChaptersError::ChapterPastEnd { .. })
+        ));
+    }
+
+    #[test]
+    fn parses_format_names() {
+        for format in ChapterFormat::ALL {
+            assert_eq!(format.to_string().parse::<ChapterFormat>(), Ok(format));
+        }
+        assert!("srt".parse::<ChapterFormat>().is_err());
+    }
+}
@@ -0,0 +1,267 @@
+use std::str::FromStr;
+
+use crate::parse_outline::{quote_attribute_value, split_attributes};
+use crate::types::{OutlineEntry, TimeCode};
+
+/// How time codes are written when formatting an outline.
+///
+/// SMPTE time codes with frames are always written as `H:MM:SS:FF`, and
+/// fractions of a second are kept in every style.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum TimeCodeStyle {
+    /// `M:SS`, or `H:MM:SS` from the first hour.
+    #[default]
+    Short,
+    /// Always `H:MM:SS`, like `0:01:10`.
+    Hours,
+    /// Always `MM:SS`, like `01:10`, or `H:MM:SS` from the first hour since
+    /// minutes can't go past 59.
+    Minutes,
+}
+
+impl TimeCodeStyle {
+    pub fn format(&self, time_code: &TimeCode) -> String {
+        if time_code.frames.is_some() {
+            return time_code.to_string();
+        }
+        let TimeCode {
+            hours,
+            minutes,
+            seconds,
+            milliseconds,
+            ..
+        } = *time_code;
+        let mut text = match self {
+            TimeCodeStyle::Hours => format!("{}:{:02}:{:02}", hours, minutes, seconds),
+            _ if hours > 0 => format!("{}:{:02}:{:02}", hours, minutes, seconds),
+            TimeCodeStyle::Short => format!("{}:{:02}", minutes, seconds),
+            TimeCodeStyle::Minutes => format!("{:02}:{:02}", minutes, seconds),
+        };
+        if milliseconds > 0 {
+            text += &format!(".{:03}", milliseconds);
+        }
+        text
+    }
+}
+
+impl FromStr for TimeCodeStyle {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<TimeCodeStyle, Self::Err> {
+        match text.to_lowercase().as_str() {
+            "short" | "m:ss" => Ok(TimeCodeStyle::Short),
+            "hours" | "h:mm:ss" => Ok(TimeCodeStyle::Hours),
+            "minutes" | "mm:ss" => Ok(TimeCodeStyle::Minutes),
+            _ => Err(format!(
+                "Unknown time code style '{}', expected short, hours or minutes",
+                text
+            )),
+        }
+    }
+}
+
+/// Writes outline entries back out as a text outline, indenting nested
+/// entries and keeping their attributes.
+pub fn format_outline(outline_entries: &[OutlineEntry]) -> String {
+    format_outline_with_style(outline_entries, TimeCodeStyle::Short)
+}
+
+/// Writes outline entries as a text outline with time codes in `style`.
+///
+/// Parsing the result gives back the same entries, as long as SMPTE time
+/// codes are read at the frame rate they were parsed at. Notes, and links with
+/// spaces or quotes, are quoted, and a title that would otherwise be read as
+/// having attributes, like `Robots | hidden`, is ended with a `|`.
+pub fn format_outline_with_style(outline_entries: &[OutlineEntry], style: TimeCodeStyle) -> String {
+    outline_entries
+        .iter()
+        .map(|entry| {
+            let mut attributes = [("url", &entry.url), ("img", &entry.img)]
+                .into_iter()
+                .filter_map(|(key, value)| {
+                    value.as_ref().map(|value| {
+                        if value.contains(|c: char| c.is_whitespace() || c == '"') {
+                            format!(" {}={}", key, quote_attribute_value(value))
+                        } else {
+                            format!(" {}={}", key, value)
+                        }
+                    })
+                })
+                .collect::<String>();
+            if let Some(notes) = &entry.notes {
+                attributes += &format!(" notes={}", quote_attribute_value(notes));
+            }
+            if entry.hidden {
+                attributes += " hidden";
+            }
+            let separator =
+                if !attributes.is_empty() || split_attributes(&entry.text).0 != entry.text {
+                    " |"
+                } else {
+                    ""
+                };
+            format!(
+                "{}{} {}{}{}\n",
+                "  ".repeat(entry.level),
+                style.format(&entry.time_code),
+                entry.text,
+                separator,
+                attributes
+            )
+        })
+        .collect()
+}
+
+/// Cleans up an outline before it's formatted.
+///
+/// Runs of whitespace in titles and notes become single spaces, entries are
+/// sorted by time, exact duplicates are removed, and entries nested more than
+/// one level below the entry before them are moved up so the outline can be
+/// parsed again.
+pub fn tidy_outline(outline_entries: &[OutlineEntry]) -> Vec<OutlineEntry> {
+    let collapse = |text: &str| text.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+    let mut entries = outline_entries
+        .iter()
+        .map(|entry| OutlineEntry {
+            text: collapse(&entry.text),
+            notes: entry.notes.as_deref().map(collapse),
+            ..entry.clone()
+        })
+        .collect::<Vec<OutlineEntry>>();
+    entries.sort();
+
+    let mut tidied: Vec<OutlineEntry> = vec![];
+    for mut entry in entries {
+        let is_duplicate = tidied
+            .iter()
+            .rev()
+            .take_while(|earlier| earlier.time_code == entry.time_code)
+            .any(|earlier| *earlier == entry);
+        if is_duplicate {
+            continue;
+        }
+        entry.level = entry
+            .level
+            .min(tidied.last().map_or(0, |previous| previous.level + 1));
+        tidied.push(entry);
+    }
+    tidied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_outline, parse_outline_with_frame_rate, FrameRate};
+
+    #[test]
+    fn formats_outline_with_attributes() {
+        let outline = "0:00 Start | url=https://example.com\n  1:10 Robots | Part 2\n2:00 Demo | notes=\"Ask about Rae\" hidden\n";
+        let entries = parse_outline(outline).expect("parse_outline should succeed");
+
+        assert_eq!(format_outline(&entries), outline);
+    }
+
+    #[test]
+    fn keeps_titles_that_look_like_attributes() {
+        let entries = vec![
+            OutlineEntry {
+                text: "Robots | hidden".to_string(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                text: "Pipes | key=value".to_string(),
+                notes: Some("Say \"hi\"".to_string()),
+                ..Default::default()
+            },
+        ];
+        let formatted = format_outline(&entries);
+
+        assert_eq!(
+            formatted,
+            "0:00 Robots | hidden |\n0:00 Pipes | key=value | notes=\"Say \\\"hi\\\"\"\n"
+        );
+        assert_eq!(parse_outline(&formatted).unwrap(), entries);
+    }
+
+    #[test]
+    fn formats_time_codes_in_each_style() {
+        let entries = parse_outline("0:05 Start\n1:10.5 Robots\n1:02:03 Links").unwrap();
+
+        assert_eq!(
+            format_outline_with_style(&entries, TimeCodeStyle::Hours),
+            "0:00:05 Start\n0:01:10.500 Robots\n1:02:03 Links\n"
+        );
+        assert_eq!(
+            format_outline_with_style(&entries, TimeCodeStyle::Minutes),
+            "00:05 Start\n01:10.500 Robots\n1:02:03 Links\n"
+        );
+    }
+
+    #[test]
+    fn tidies_outline() {
+        let outline = "1:10 Robots\n0:00   Start  here\n    0:30 Background\n1:10 Robots\n1:10 Robots | hidden\n";
+        let entries = parse_outline(outline).unwrap();
+
+        assert_eq!(
+            format_outline(&tidy_outline(&entries)),
+            "0:00 Start here\n  0:30 Background\n1:10 Robots\n1:10 Robots | hidden\n"
+        );
+    }
+
+    #[test]
+    fn moves_up_entries_without_a_parent() {
+        let entries = vec![
+            OutlineEntry {
+                text: "Aside".to_string(),
+                level: 2,
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 1, 0).unwrap(),
+                text: "Deep".to_string(),
+                level: 3,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            format_outline(&tidy_outline(&entries)),
+            "0:00 Aside\n  1:00 Deep\n"
+        );
+    }
+
+    #[test]
+    fn parses_formatted_outline_back_to_the_same_entries() {
+        let outlines = [
+            "0:00 Start | url=https://example.com img=https://example.com/a.png\n  1:10.5 Robots | Part 2\n    2:00 Rae | notes=\"Ask about the demo\" hidden\n1:00:00 Links\n",
+            "0:45 Aside\n3:00 Late\n0:10 Early\n0:10 Early\n",
+            "00:00:10:15 Frames\n01:00:00:00 Hour\n",
+            "0:00 Quotes | notes=\"Ask about \\\"robots\\\" and C:\\\\ | D:\\\\\"\n",
+            "0:00 Spaces | url=\"https://example.com/a b\" img=\"https://example.com/c\td.png\"\n",
+            "0:00 Robots | hidden |\n1:00 Robots | url=https://example.com |\n2:00 Pipes | |\n",
+            "0:00 Robots | hidden | hidden\n1:00 Robots | url=a | url=https://example.com\n",
+        ];
+        for outline in outlines {
+            for style in [
+                TimeCodeStyle::Short,
+                TimeCodeStyle::Hours,
+                TimeCodeStyle::Minutes,
+            ] {
+                let entries = tidy_outline(
+                    &parse_outline_with_frame_rate(outline, FrameRate::Fps25).unwrap(),
+                );
+                let formatted = format_outline_with_style(&entries, style);
+
+                assert_eq!(
+                    parse_outline_with_frame_rate(&formatted, FrameRate::Fps25).unwrap(),
+                    entries
+                );
+                assert_eq!(
+                    format_outline_with_style(&tidy_outline(&entries), style),
+                    formatted
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use id3::frame::{Chapter, ExtendedLink, TableOfContents};
+use id3::{Content, Frame, Tag, TagLike, Version};
+
+use crate::audio::mp3_duration;
+use crate::chapter::{episode_end, ChapterError};
+use crate::error::ErrorKind;
+use crate::types::{OutlineEntry, TimeCode};
+
+const TABLE_OF_CONTENTS_ID: &str = "toc";
+
+#[derive(Debug)]
+pub enum Id3ChaptersError {
+    Io(std::io::Error),
+    Id3(id3::Error),
+    UnknownDuration,
+    Chapter(ChapterError),
+    NoChapters,
+}
+
+impl std::fmt::Display for Id3ChaptersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Id3ChaptersError::Io(e) => write!(f, "Could not read the audio: {}", e),
+            Id3ChaptersError::Id3(e) => write!(f, "Invalid ID3 tag: {}", e),
+            Id3ChaptersError::UnknownDuration => {
+                write!(f, "Could not find the length of the audio")
+            }
+            Id3ChaptersError::Chapter(e) => write!(f, "{}", e),
+            Id3ChaptersError::NoChapters => write!(f, "The audio has no ID3 chapters"),
+        }
+    }
+}
+
+impl std::error::Error for Id3ChaptersError {}
+
+impl Id3ChaptersError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Id3ChaptersError::Io(_) => ErrorKind::Io,
+            Id3ChaptersError::Chapter(e) => e.kind(),
+            _ => ErrorKind::Media,
+        }
+    }
+}
+
+impl From<ChapterError> for Id3ChaptersError {
+    fn from(e: ChapterError) -> Id3ChaptersError {
+        Id3ChaptersError::Chapter(e)
+    }
+}
+
+impl From<std::io::Error> for Id3ChaptersError {
+    fn from(e: std::io::Error) -> Id3ChaptersError {
+        Id3ChaptersError::Io(e)
+    }
+}
+
+impl From<id3::Error> for Id3ChaptersError {
+    fn from(e: id3::Error) -> Id3ChaptersError {
+        Id3ChaptersError::Id3(e)
+    }
+}
+
+/// The ID3v2 version to write tags as.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Id3Version {
+    Id3v23,
+    Id3v24,
+}
+
+impl From<Id3Version> for Version {
+    fn from(version: Id3Version) -> Version {
+        match version {
+            Id3Version::Id3v23 => Version::Id3v23,
+            Id3Version::Id3v24 => Version::Id3v24,
+        }
+    }
+}
+
+impl FromStr for Id3Version {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Id3Version, Self::Err> {
+        match text.to_lowercase().trim_start_matches("id3v") {
+            "2.3" | "23" => Ok(Id3Version::Id3v23),
+            "2.4" | "24" => Ok(Id3Version::Id3v24),
+            _ => Err(format!(
+                "Unknown ID3 version '{}', expected 2.3 or 2.4",
+                text
+            )),
+        }
+    }
+}
+
+/// Replaces the chapters in an MP3's ID3 tag with the outline, keeping every
+/// other frame of the tag.
+///
+/// Every entry becomes a `CHAP` frame with a `TIT2` title and, if it has a
+/// URL, a `WXXX` link. Each chapter ends where the next one starts, and the
+/// last one ends with the audio. A top level `CTOC` frame lists the chapters
+/// in order, leaving out hidden ones.
+pub fn write_id3_chapters(
+    path: &Path,
+    entries: &[OutlineEntry],
+    version: Id3Version,
+) -> Result<(), Id3ChaptersError> {
+    let duration =
+        mp3_duration(&mut std::fs::File::open(path)?)?.ok_or(Id3ChaptersError::UnknownDuration)?;
+    let mut tag = match Tag::read_from_path(path) {
+        Ok(tag) => tag,
+        Err(id3::Error {
+            kind: id3::ErrorKind::NoTag,
+            ..
+        }) => Tag::new(),
+        Err(e) => return Err(e.into()),
+    };
+    set_chapters(&mut tag, entries, duration)?;
+    tag.write_to_path(path, version.into())?;
+    Ok(())
+}
+
+/// Reads the chapters from an MP3's ID3 tag as an outline.
+///
+/// Chapters are read in time order. When the tag has a table of contents,
+/// chapters it leaves out are read as hidden.
+pub fn read_id3_chapters(path: &Path) -> Result<Vec<OutlineEntry>, Id3ChaptersError> {
+    let tag = match Tag::read_from_path(path) {
+        Ok(tag) => tag,
+        Err(id3::Error {
+            kind: id3::ErrorKind::NoTag,
+            ..
+        }) => return Err(Id3ChaptersError::NoChapters),
+        Err(e) => return Err(e.into()),
+    };
+    get_chapters(&tag)
+}
+
+fn set_chapters(
+    tag: &mut Tag,
+    entries: &[OutlineEntry],
+    duration: Duration,
+) -> Result<(), Id3ChaptersError> {
+    let mut entries = entries.to_vec();
+    entries.sort();
+    episode_end(&entries, duration)?;
+
+    tag.remove_all_chapters();
+    tag.remove_all_tables_of_contents();
+
+    let element_ids = (0..entries.len())
+        .map(|index| format!("chp{}", index))
+        .collect::<Vec<String>>();
+    for (index, entry) in entries.iter().enumerate() {
+        let end_time = entries
+            .get(index + 1)
+            .map_or(duration, |next| next.time_code.to_duration());
+        let mut frames = vec![Frame::text("TIT2", entry.text.clone())];
+        if let Some(url) = &entry.url {
+            frames.push(Frame::with_content(
+                "WXXX",
+                Content::ExtendedLink(ExtendedLink {
+                    description: String::new(),
+                    link: url.clone(),
+                }),
+            ));
+        }
+        tag.add_frame(Chapter {
+            element_id: element_ids[index].clone(),
+            start_time: entry.time_code.to_duration().as_millis() as u32,
+            end_time: end_time.as_millis() as u32,
+            start_offset: u32::MAX,
+            end_offset: u32::MAX,
+            frames,
+        });
+    }
+    tag.add_frame(TableOfContents {
+        element_id: TABLE_OF_CONTENTS_ID.to_string(),
+        top_level: true,
+        ordered: true,
+        elements: element_ids
+            .into_iter()
+            .zip(&entries)
+            .filter(|(_, entry)| !entry.hidden)
+            .map(|(element_id, _)| element_id)
+            .collect(),
+        frames: vec![],
+    });
+    Ok(())
+}
+
+fn get_chapters(tag: &Tag) -> Result<Vec<OutlineEntry>, Id3ChaptersError> {
+    let mut chapters = tag.chapters().collect::<Vec<&Chapter>>();
+    if chapters.is_empty() {
+        return Err(Id3ChaptersError::NoChapters);
+    }
+    chapters.sort_by_key(|chapter| chapter.start_time);
+    let toc = tag.tables_of_contents().find(|toc| toc.top_level);
+
+    Ok(chapters
+        .into_iter()
+        .map(|chapter| {
+            let frame = |id: &str| chapter.frames.iter().find(|frame| frame.id() == id);
+            OutlineEntry {
+                time_code: TimeCode::from_duration(Duration::from_millis(
+                    chapter.start_time as u64,
+                ))
+                .unwrap_or_default(),
+                text: frame("TIT2")
+                    .and_then(|frame| frame.content().text())
+                    .unwrap_or_default()
+                    .to_string(),
+                url: frame("WXXX")
+                    .and_then(|frame| frame.content().extended_link())
+                    .map(|link| link.link.clone()),
+                hidden: toc.is_some_and(|toc| !toc.elements.contains(&chapter.element_id)),
+                ..Default::default()
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_outline;
+
+    /// Ten seconds of MPEG 1 layer III frames at 128 kbps and 48 kHz.
+    fn mp3_audio() -> Vec<u8> {
+        let mut frame = vec![0; 384];
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x94, 0x00]);
+        frame.repeat(10 * 48000 / 1152 + 1)
+    }
+
+    #[test]
+    fn writes_and_reads_back_chapters() {
+        let path = std::env::temp_dir().join("id3_chapters_round_trip.mp3");
+        std::fs::write(&path, mp3_audio()).unwrap();
+        let mut tag = Tag::new();
+        tag.set_title("Episode 1");
+        tag.write_to_path(&path, Version::Id3v24).unwrap();
+
+        let entries = parse_outline(
+            "0:00 Start | url=https://example.com\n0:05.5 Robots\n0:08 Aside | hidden",
+        )
+        .expect("parse_outline should succeed");
+        write_id3_chapters(&path, &entries, Id3Version::Id3v23)
+            .expect("write_id3_chapters should succeed");
+
+        let tag = Tag::read_from_path(&path).unwrap();
+        let read_back = read_id3_chapters(&path).expect("read_id3_chapters should succeed");
+        let audio_length = std::fs::metadata(&path).unwrap().len() as usize;
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(read_back, entries);
+        assert_eq!(tag.title(), Some("Episode 1"));
+        assert_eq!(tag.version(), Version::Id3v23);
+        let chapters = tag.chapters().collect::<Vec<&Chapter>>();
+        assert_eq!(chapters[0].end_time, 5500);
+        assert_eq!(chapters[1].end_time, 8000);
+        assert_eq!(chapters[2].end_time, 10008);
+        let toc = tag.tables_of_contents().next().unwrap();
+        assert_eq!(toc.elements, vec!["chp0", "chp1"]);
+        assert!(audio_length > mp3_audio().len());
+    }
+
+    #[test]
+    fn rejects_chapters_after_the_audio() {
+        let mut tag = Tag::new();
+        let entries = parse_outline("0:00 Start\n0:11 Too late").unwrap();
+
+        assert!(matches!(
+            set_chapters(&mut tag, &entries, Duration::from_secs(10)),
+            Err(Id3ChaptersError::Chapter(
+                ChapterError::ChapterPastEnd { .. }
+            ))
+        ));
+    }
+}
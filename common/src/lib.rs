@@ -1,5 +1,56 @@
+pub mod error;
+pub use error::{read_file, read_yaml, write_file, ErrorKind, FileError};
+
 pub mod types;
-pub use types::{OutlineEntry, TimeCode};
+pub use types::{FrameRate, OutlineEntry, TimeCode};
 
 pub mod parse_outline;
-pub use parse_outline::parse_outline;
+pub use parse_outline::{parse_outline, parse_outline_with_frame_rate};
+
+pub mod format_outline;
+pub use format_outline::{format_outline, format_outline_with_style, tidy_outline, TimeCodeStyle};
+
+pub mod shift;
+pub use shift::{shift_outline, shift_time_code, EditOperation};
+
+pub mod validate_outline;
+pub use validate_outline::{check_outline, validate_outline, ChapterRules};
+
+pub mod parse_fcpxml;
+pub use parse_fcpxml::parse_fcpxml;
+
+pub mod parse_markers;
+pub use parse_markers::{
+    parse_audacity_labels, parse_premiere_csv, parse_reaper_csv, parse_resolve_edl,
+};
+
+pub mod parse_yaml_outline;
+pub use parse_yaml_outline::parse_yaml_outline;
+
+pub mod outline_source;
+pub use outline_source::{OutlineFormat, OutlineOptions, OutlineSource};
+
+pub mod read_outline;
+pub use read_outline::{read_outline, read_outline_with_format};
+
+pub mod chapter;
+pub use chapter::Chapter;
+
+pub mod podcast_chapters;
+pub use podcast_chapters::PodcastChapters;
+
+pub mod audio;
+
+pub mod cli;
+
+pub mod id3_chapters;
+pub use id3_chapters::{read_id3_chapters, write_id3_chapters, Id3Version};
+
+pub mod mp4_chapters;
+pub use mp4_chapters::{read_mp4_chapters, write_mp4_chapters, Mp4ChapterKinds};
+
+pub mod export_chapters;
+pub use export_chapters::{ChapterExporter, ChapterFormat};
+
+pub mod slug;
+pub use slug::{slugify, SlugStyle, Slugger};
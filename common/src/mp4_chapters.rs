@@ -0,0 +1,278 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use mp4ameta::{Chapter, Tag};
+
+use crate::chapter::{episode_end, ChapterError};
+use crate::error::ErrorKind;
+use crate::types::{OutlineEntry, TimeCode};
+
+#[derive(Debug)]
+pub enum Mp4ChaptersError {
+    Mp4(mp4ameta::Error),
+    Chapter(ChapterError),
+    NoChapters,
+}
+
+impl std::fmt::Display for Mp4ChaptersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mp4ChaptersError::Mp4(e) => write!(f, "Invalid MP4 file: {}", e),
+            Mp4ChaptersError::Chapter(e) => write!(f, "{}", e),
+            Mp4ChaptersError::NoChapters => write!(f, "The file has no MP4 chapters"),
+        }
+    }
+}
+
+impl std::error::Error for Mp4ChaptersError {}
+
+impl Mp4ChaptersError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Mp4ChaptersError::Chapter(e) => e.kind(),
+            _ => ErrorKind::Media,
+        }
+    }
+}
+
+impl From<ChapterError> for Mp4ChaptersError {
+    fn from(e: ChapterError) -> Mp4ChaptersError {
+        Mp4ChaptersError::Chapter(e)
+    }
+}
+
+impl From<mp4ameta::Error> for Mp4ChaptersError {
+    fn from(e: mp4ameta::Error) -> Mp4ChaptersError {
+        Mp4ChaptersError::Mp4(e)
+    }
+}
+
+/// Where chapters are stored in an MP4 file.
+///
+/// Apple's players read a QuickTime chapter text track, while many others
+/// only read the Nero `chpl` chapter list, so both are written by default.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Mp4ChapterKinds {
+    Track,
+    List,
+    Both,
+}
+
+impl Mp4ChapterKinds {
+    fn track(&self) -> bool {
+        matches!(self, Mp4ChapterKinds::Track | Mp4ChapterKinds::Both)
+    }
+
+    fn list(&self) -> bool {
+        matches!(self, Mp4ChapterKinds::List | Mp4ChapterKinds::Both)
+    }
+}
+
+impl FromStr for Mp4ChapterKinds {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Mp4ChapterKinds, Self::Err> {
+        match text.to_lowercase().as_str() {
+            "track" => Ok(Mp4ChapterKinds::Track),
+            "list" | "chpl" => Ok(Mp4ChapterKinds::List),
+            "both" => Ok(Mp4ChapterKinds::Both),
+            _ => Err(format!(
+                "Unknown MP4 chapter kind '{}', expected track, list or both",
+                text
+            )),
+        }
+    }
+}
+
+/// Replaces the chapters in an M4A or MP4 file with the outline.
+///
+/// Only the container's metadata is rewritten, so the audio and video are
+/// left as they are, along with any other metadata. MP4 chapters have no way
+/// to stay out of the chapter menu, so hidden entries are left out.
+pub fn write_mp4_chapters(
+    path: &Path,
+    entries: &[OutlineEntry],
+    kinds: Mp4ChapterKinds,
+) -> Result<(), Mp4ChaptersError> {
+    let mut tag = Tag::read_from_path(path)?;
+    let chapters = to_chapters(entries, tag.duration())?;
+
+    tag.chapter_track_mut().clear();
+    tag.chapter_list_mut().clear();
+    if kinds.track() {
+        tag.chapter_track_mut().extend(chapters.iter().cloned());
+    }
+    if kinds.list() {
+        tag.chapter_list_mut().extend(chapters);
+    }
+    tag.write_to_path(path)?;
+    Ok(())
+}
+
+/// Reads the chapters of an M4A or MP4 file as an outline, preferring the
+/// chapter track over the chapter list when a file has both.
+pub fn read_mp4_chapters(path: &Path) -> Result<Vec<OutlineEntry>, Mp4ChaptersError> {
+    let tag = Tag::read_from_path(path)?;
+    let chapters = match tag.chapter_track() {
+        [] => tag.chapter_list(),
+        chapters => chapters,
+    };
+    if chapters.is_empty() {
+        return Err(Mp4ChaptersError::NoChapters);
+    }
+
+    Ok(chapters
+        .iter()
+        .map(|chapter| OutlineEntry {
+            time_code: TimeCode::from_duration(chapter.start).unwrap_or_default(),
+            text: chapter.title.clone(),
+            ..Default::default()
+        })
+        .collect())
+}
+
+fn to_chapters(
+    entries: &[OutlineEntry],
+    duration: Duration,
+) -> Result<Vec<Chapter>, Mp4ChaptersError> {
+    let mut entries = entries
+        .iter()
+        .filter(|entry| !entry.hidden)
+        .cloned()
+        .collect::<Vec<OutlineEntry>>();
+    entries.sort();
+    episode_end(&entries, duration)?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| Chapter::new(entry.time_code.to_duration(), entry.text))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_outline;
+
+    fn atom(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut atom = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        atom.extend(kind);
+        atom.extend(payload);
+        atom
+    }
+
+    fn full_atom(kind: &[u8; 4], flags: u32, payload: &[u8]) -> Vec<u8> {
+        atom(kind, &[&flags.to_be_bytes()[..], payload].concat())
+    }
+
+    const MATRIX: [u32; 9] = [0x10000, 0, 0, 0, 0x10000, 0, 0, 0, 0x40000000];
+
+    fn be(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_be_bytes())
+            .collect()
+    }
+
+    /// A ten second M4A file with one silent sample.
+    fn m4a_audio() -> Vec<u8> {
+        let ftyp = atom(b"ftyp", b"M4A \0\0\0\0M4A isom");
+        let moov = |chunk_offset: u32| {
+            let mvhd = full_atom(
+                b"mvhd",
+                0,
+                &[
+                    be(&[0, 0, 1000, 10_000, 0x10000]),
+                    vec![0x01, 0x00],
+                    vec![0; 10],
+                    be(&MATRIX),
+                    vec![0; 24],
+                    be(&[2]),
+                ]
+                .concat(),
+            );
+            let tkhd = full_atom(
+                b"tkhd",
+                7,
+                &[
+                    be(&[0, 0, 1, 0, 10_000, 0, 0]),
+                    vec![0, 0, 0, 0, 0x01, 0x00, 0, 0],
+                    be(&MATRIX),
+                    be(&[0, 0]),
+                ]
+                .concat(),
+            );
+            let mdhd = full_atom(
+                b"mdhd",
+                0,
+                &[be(&[0, 0, 44100, 441_000]), vec![0x55, 0xC4, 0, 0]].concat(),
+            );
+            let hdlr = full_atom(
+                b"hdlr",
+                0,
+                &[be(&[0]), b"soun".to_vec(), vec![0; 13]].concat(),
+            );
+            let dinf = atom(
+                b"dinf",
+                &full_atom(b"dref", 0, &[be(&[1]), full_atom(b"url ", 1, &[])].concat()),
+            );
+            let stbl = atom(
+                b"stbl",
+                &[
+                    full_atom(b"stsd", 0, &be(&[0])),
+                    full_atom(b"stts", 0, &be(&[1, 1, 441_000])),
+                    full_atom(b"stsc", 0, &be(&[1, 1, 1, 1])),
+                    full_atom(b"stsz", 0, &be(&[0, 1, 8])),
+                    full_atom(b"stco", 0, &be(&[1, chunk_offset])),
+                ]
+                .concat(),
+            );
+            let minf = atom(
+                b"minf",
+                &[full_atom(b"smhd", 0, &[0; 4]), dinf, stbl].concat(),
+            );
+            let mdia = atom(b"mdia", &[mdhd, hdlr, minf].concat());
+            let trak = atom(b"trak", &[tkhd, mdia].concat());
+            atom(b"moov", &[mvhd, trak].concat())
+        };
+        let chunk_offset = (ftyp.len() + moov(0).len() + 8) as u32;
+        [ftyp, moov(chunk_offset), atom(b"mdat", &[0; 8])].concat()
+    }
+
+    #[test]
+    fn writes_and_reads_back_chapters() {
+        let outline = "0:00 Start\n0:05.5 Robots\n";
+        let entries = parse_outline(outline).expect("parse_outline should succeed");
+
+        for (name, kinds) in [
+            ("track", Mp4ChapterKinds::Track),
+            ("list", Mp4ChapterKinds::List),
+            ("both", Mp4ChapterKinds::Both),
+        ] {
+            let path = std::env::temp_dir().join(format!("mp4_chapters_{}.m4a", name));
+            std::fs::write(&path, m4a_audio()).unwrap();
+
+            write_mp4_chapters(&path, &entries, kinds).expect("write_mp4_chapters should succeed");
+            let tag = Tag::read_from_path(&path).unwrap();
+            let read_back = read_mp4_chapters(&path).expect("read_mp4_chapters should succeed");
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(read_back, entries);
+            assert_eq!(tag.chapter_track().is_empty(), !kinds.track());
+            assert_eq!(tag.chapter_list().is_empty(), !kinds.list());
+        }
+    }
+
+    #[test]
+    fn rejects_chapters_after_the_audio() {
+        let entries = parse_outline("0:00 Start\n0:11 Too late").unwrap();
+
+        assert!(matches!(
+            to_chapters(&entries, Duration::from_secs(10)),
+            Err(Mp4ChaptersError::Chapter(
+                ChapterError::ChapterPastEnd { .. }
+            ))
+        ));
+    }
+}
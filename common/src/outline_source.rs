@@ -0,0 +1,332 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::parse_fcpxml::{parse_fcpxml, MarkerFilter, ParseFcpxmlError};
+use crate::parse_markers::{
+    parse_audacity_labels, parse_premiere_csv, parse_reaper_csv, parse_resolve_edl,
+    ParseMarkersError,
+};
+use crate::parse_outline::{parse_outline_with_frame_rate, ParseOutlineErrors};
+use crate::parse_yaml_outline::{parse_yaml_outline, ParseYamlOutlineError};
+use crate::types::{FrameRate, OutlineEntry, TimeCode};
+
+/// How to read an outline file.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct OutlineOptions {
+    /// The format of the outline, or `None` to detect it from the file.
+    pub format: Option<OutlineFormat>,
+    /// Frame rate of SMPTE time codes in the outline.
+    pub frame_rate: FrameRate,
+    /// Which Final Cut Pro markers become outline entries.
+    pub fcpxml_markers: MarkerFilter,
+}
+
+impl Default for OutlineOptions {
+    fn default() -> OutlineOptions {
+        OutlineOptions {
+            format: None,
+            frame_rate: TimeCode::DEFAULT_FRAME_RATE,
+            fcpxml_markers: MarkerFilter::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum OutlineSourceError {
+    Outline(ParseOutlineErrors),
+    Yaml(ParseYamlOutlineError),
+    Fcpxml(ParseFcpxmlError),
+    Markers(ParseMarkersError),
+}
+
+impl std::fmt::Display for OutlineSourceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutlineSourceError::Outline(errors) => write!(f, "{}", errors),
+            OutlineSourceError::Yaml(e) => write!(f, "{}", e),
+            OutlineSourceError::Fcpxml(e) => write!(f, "{}", e),
+            OutlineSourceError::Markers(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for OutlineSourceError {}
+
+/// Something outline entries can be read from, like a text outline or an
+/// editor's marker export.
+pub trait OutlineSource {
+    /// Whether `contents`, read from `path`, look like this source.
+    fn detect(&self, path: &Path, contents: &str) -> bool;
+
+    fn parse(
+        &self,
+        contents: &str,
+        options: &OutlineOptions,
+    ) -> Result<Vec<OutlineEntry>, OutlineSourceError>;
+}
+
+/// The outline formats that can be read.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum OutlineFormat {
+    /// Time codes followed by chapter names, one per line.
+    Text,
+    /// A YAML list of chapters, with sub-topics nested under `chapters`.
+    Yaml,
+    /// A Final Cut Pro `.fcpxml` export or `.fcpxmld` bundle.
+    Fcpxml,
+    /// A marker EDL exported from DaVinci Resolve.
+    ResolveEdl,
+    /// A marker list exported from Premiere Pro.
+    PremiereCsv,
+    /// An Audacity label track export.
+    AudacityLabels,
+    /// A marker and region list exported from Reaper.
+    ReaperCsv,
+}
+
+impl OutlineFormat {
+    /// Every format, in the order they are tried when detecting a format.
+    pub const ALL: [OutlineFormat; 7] = [
+        OutlineFormat::Yaml,
+        OutlineFormat::Fcpxml,
+        OutlineFormat::ResolveEdl,
+        OutlineFormat::PremiereCsv,
+        OutlineFormat::ReaperCsv,
+        OutlineFormat::AudacityLabels,
+        OutlineFormat::Text,
+    ];
+
+    /// Picks the format of `contents` read from `path`, from the extension and
+    /// the first lines. Anything unrecognized is read as a text outline.
+    pub fn detect(path: &Path, contents: &str) -> OutlineFormat {
+        OutlineFormat::ALL
+            .into_iter()
+            .find(|format| format.source().detect(path, contents))
+            .unwrap_or(OutlineFormat::Text)
+    }
+
+    pub fn source(&self) -> &'static dyn OutlineSource {
+        match self {
+            OutlineFormat::Text => &TextOutline,
+            OutlineFormat::Yaml => &YamlOutline,
+            OutlineFormat::Fcpxml => &FcpxmlOutline,
+            OutlineFormat::ResolveEdl => &ResolveEdl,
+            OutlineFormat::PremiereCsv => &PremiereCsv,
+            OutlineFormat::AudacityLabels => &AudacityLabels,
+            OutlineFormat::ReaperCsv => &ReaperCsv,
+        }
+    }
+}
+
+impl FromStr for OutlineFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<OutlineFormat, Self::Err> {
+        match text.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "text" | "txt" => Ok(OutlineFormat::Text),
+            "yaml" | "yml" => Ok(OutlineFormat::Yaml),
+            "fcpxml" | "finalcut" | "finalcutpro" => Ok(OutlineFormat::Fcpxml),
+            "edl" | "resolve" | "resolveedl" => Ok(OutlineFormat::ResolveEdl),
+            "premiere" | "premierecsv" => Ok(OutlineFormat::PremiereCsv),
+            "audacity" | "audacitylabels" => Ok(OutlineFormat::AudacityLabels),
+            "reaper" | "reapercsv" => Ok(OutlineFormat::ReaperCsv),
+            _ => Err(format!(
+                "Unknown outline format '{}', expected text, yaml, fcpxml, resolve, premiere, audacity or reaper",
+                text
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for OutlineFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            OutlineFormat::Text => "text",
+            OutlineFormat::Yaml => "yaml",
+            OutlineFormat::Fcpxml => "fcpxml",
+            OutlineFormat::ResolveEdl => "resolve",
+            OutlineFormat::PremiereCsv => "premiere",
+            OutlineFormat::AudacityLabels => "audacity",
+            OutlineFormat::ReaperCsv => "reaper",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .is_some_and(|extension| extensions.iter().any(|e| extension.eq_ignore_ascii_case(e)))
+}
+
+fn first_line(contents: &str) -> &str {
+    contents
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .unwrap_or_default()
+}
+
+pub struct TextOutline;
+
+impl OutlineSource for TextOutline {
+    fn detect(&self, path: &Path, _contents: &str) -> bool {
+        has_extension(path, &["txt", "md"])
+    }
+
+    fn parse(
+        &self,
+        contents: &str,
+        options: &OutlineOptions,
+    ) -> Result<Vec<OutlineEntry>, OutlineSourceError> {
+        parse_outline_with_frame_rate(contents, options.frame_rate)
+            .map_err(OutlineSourceError::Outline)
+    }
+}
+
+pub struct YamlOutline;
+
+impl OutlineSource for YamlOutline {
+    fn detect(&self, path: &Path, _contents: &str) -> bool {
+        has_extension(path, &["yaml", "yml"])
+    }
+
+    fn parse(
+        &self,
+        contents: &str,
+        options: &OutlineOptions,
+    ) -> Result<Vec<OutlineEntry>, OutlineSourceError> {
+        parse_yaml_outline(contents, options.frame_rate).map_err(OutlineSourceError::Yaml)
+    }
+}
+
+pub struct FcpxmlOutline;
+
+impl OutlineSource for FcpxmlOutline {
+    fn detect(&self, path: &Path, contents: &str) -> bool {
+        has_extension(path, &["fcpxml", "fcpxmld"]) || contents.contains("<fcpxml")
+    }
+
+    fn parse(
+        &self,
+        contents: &str,
+        options: &OutlineOptions,
+    ) -> Result<Vec<OutlineEntry>, OutlineSourceError> {
+        parse_fcpxml(contents, &options.fcpxml_markers).map_err(OutlineSourceError::Fcpxml)
+    }
+}
+
+pub struct ResolveEdl;
+
+impl OutlineSource for ResolveEdl {
+    fn detect(&self, path: &Path, contents: &str) -> bool {
+        has_extension(path, &["edl"]) || first_line(contents).starts_with("TITLE:")
+    }
+
+    fn parse(
+        &self,
+        contents: &str,
+        options: &OutlineOptions,
+    ) -> Result<Vec<OutlineEntry>, OutlineSourceError> {
+        parse_resolve_edl(contents, options.frame_rate).map_err(OutlineSourceError::Markers)
+    }
+}
+
+pub struct PremiereCsv;
+
+impl OutlineSource for PremiereCsv {
+    fn detect(&self, _path: &Path, contents: &str) -> bool {
+        first_line(contents).starts_with("Marker Name")
+    }
+
+    fn parse(
+        &self,
+        contents: &str,
+        options: &OutlineOptions,
+    ) -> Result<Vec<OutlineEntry>, OutlineSourceError> {
+        parse_premiere_csv(contents, options.frame_rate).map_err(OutlineSourceError::Markers)
+    }
+}
+
+pub struct AudacityLabels;
+
+impl OutlineSource for AudacityLabels {
+    fn detect(&self, _path: &Path, contents: &str) -> bool {
+        let fields = first_line(contents).split('\t').collect::<Vec<&str>>();
+        fields.len() >= 3
+            && fields[..2]
+                .iter()
+                .all(|field| field.trim().parse::<f64>().is_ok())
+    }
+
+    fn parse(
+        &self,
+        contents: &str,
+        _options: &OutlineOptions,
+    ) -> Result<Vec<OutlineEntry>, OutlineSourceError> {
+        parse_audacity_labels(contents).map_err(OutlineSourceError::Markers)
+    }
+}
+
+pub struct ReaperCsv;
+
+impl OutlineSource for ReaperCsv {
+    fn detect(&self, _path: &Path, contents: &str) -> bool {
+        first_line(contents).starts_with("#,Name,Start")
+    }
+
+    fn parse(
+        &self,
+        contents: &str,
+        options: &OutlineOptions,
+    ) -> Result<Vec<OutlineEntry>, OutlineSourceError> {
+        parse_reaper_csv(contents, options.frame_rate).map_err(OutlineSourceError::Markers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_formats_from_extension_and_contents() {
+        let detect = |path: &str, contents: &str| OutlineFormat::detect(Path::new(path), contents);
+
+        assert_eq!(detect("outline.txt", "0:00 Start"), OutlineFormat::Text);
+        assert_eq!(detect("outline", "0:00 Start"), OutlineFormat::Text);
+        assert_eq!(detect("outline.yaml", "- time: 0:00"), OutlineFormat::Yaml);
+        assert_eq!(detect("Episode.fcpxmld", ""), OutlineFormat::Fcpxml);
+        assert_eq!(
+            detect("export.xml", "<?xml version=\"1.0\"?>\n<fcpxml>"),
+            OutlineFormat::Fcpxml
+        );
+        assert_eq!(detect("markers.edl", ""), OutlineFormat::ResolveEdl);
+        assert_eq!(
+            detect("markers.txt", "TITLE: Timeline 1\n"),
+            OutlineFormat::ResolveEdl
+        );
+        assert_eq!(
+            detect("markers.csv", "\u{feff}Marker Name\tDescription\tIn\n"),
+            OutlineFormat::PremiereCsv
+        );
+        assert_eq!(
+            detect("labels.txt", "0.000000\t0.000000\tIntro\n"),
+            OutlineFormat::AudacityLabels
+        );
+        assert_eq!(
+            detect("markers.csv", "#,Name,Start,End,Length,Color\n"),
+            OutlineFormat::ReaperCsv
+        );
+    }
+
+    #[test]
+    fn parses_format_names() {
+        for format in OutlineFormat::ALL {
+            assert_eq!(format.to_string().parse::<OutlineFormat>(), Ok(format));
+        }
+        assert_eq!(
+            "Resolve-EDL".parse::<OutlineFormat>(),
+            Ok(OutlineFormat::ResolveEdl)
+        );
+        assert!("word".parse::<OutlineFormat>().is_err());
+    }
+}
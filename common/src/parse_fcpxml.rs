@@ -0,0 +1,326 @@
+use roxmltree::{Document, Node, ParsingOptions};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::types::{OutlineEntry, TimeCode};
+
+#[derive(Debug)]
+pub enum ParseFcpxmlError {
+    InvalidXml(String),
+    InvalidTime(String),
+    MissingSequence,
+}
+
+impl std::fmt::Display for ParseFcpxmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseFcpxmlError::InvalidXml(error) => write!(f, "Invalid FCPXML: {}", error),
+            ParseFcpxmlError::InvalidTime(time) => write!(f, "Invalid FCPXML time: {}", time),
+            ParseFcpxmlError::MissingSequence => {
+                write!(f, "The FCPXML does not contain a project sequence")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseFcpxmlError {}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MarkerKind {
+    Standard,
+    Chapter,
+    ToDo,
+}
+
+/// A marker placed on the project timeline in Final Cut Pro.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FcpxmlMarker {
+    pub time_code: TimeCode,
+    pub text: String,
+    pub kind: MarkerKind,
+}
+
+/// Which kinds of markers become outline entries.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct MarkerFilter {
+    pub standard: bool,
+    pub chapter: bool,
+    pub to_do: bool,
+}
+
+impl MarkerFilter {
+    pub fn includes(&self, kind: MarkerKind) -> bool {
+        match kind {
+            MarkerKind::Standard => self.standard,
+            MarkerKind::Chapter => self.chapter,
+            MarkerKind::ToDo => self.to_do,
+        }
+    }
+}
+
+/// Chapter and standard markers, leaving out to-do markers.
+impl Default for MarkerFilter {
+    fn default() -> MarkerFilter {
+        MarkerFilter {
+            standard: true,
+            chapter: true,
+            to_do: false,
+        }
+    }
+}
+
+/// Parses a comma separated list of marker kinds, like `chapter,standard`.
+impl FromStr for MarkerFilter {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<MarkerFilter, Self::Err> {
+        let mut filter = MarkerFilter {
+            standard: false,
+            chapter: false,
+            to_do: false,
+        };
+        for kind in text.split(',').map(|kind| kind.trim().to_lowercase()) {
+            match kind.replace(['-', '_'], "").as_str() {
+                "standard" => filter.standard = true,
+                "chapter" => filter.chapter = true,
+                "todo" => filter.to_do = true,
+                _ => {
+                    return Err(format!(
+                        "Unknown marker kind '{}', expected chapter, standard or todo",
+                        kind
+                    ))
+                }
+            }
+        }
+        Ok(filter)
+    }
+}
+
+/// Reads the markers of the first project in an FCPXML export as outline entries.
+pub fn parse_fcpxml(
+    xml: &str,
+    filter: &MarkerFilter,
+) -> Result<Vec<OutlineEntry>, ParseFcpxmlError> {
+    Ok(parse_fcpxml_markers(xml)?
+        .into_iter()
+        .filter(|marker| filter.includes(marker.kind))
+        .map(|marker| OutlineEntry {
+            time_code: marker.time_code,
+            text: marker.text,
+            ..Default::default()
+        })
+        .collect())
+}
+
+/// Reads every marker of the first project in an FCPXML export, in timeline order.
+///
+/// Marker times are relative to the clip they're attached to, so they are
+/// moved onto the project timeline using each clip's `offset` and `start`,
+/// and then made relative to the start of the project.
+pub fn parse_fcpxml_markers(xml: &str) -> Result<Vec<FcpxmlMarker>, ParseFcpxmlError> {
+    let options = ParsingOptions {
+        allow_dtd: true,
+        ..ParsingOptions::default()
+    };
+    let document = Document::parse_with_options(xml, options)
+        .map_err(|e| ParseFcpxmlError::InvalidXml(e.to_string()))?;
+    let sequence = document
+        .descendants()
+        .find(|node| node.has_tag_name("sequence"))
+        .ok_or(ParseFcpxmlError::MissingSequence)?;
+    let sequence_start = parse_time(sequence.attribute("tcStart").unwrap_or("0s"))?;
+
+    let mut markers = vec![];
+    collect_markers(sequence, 0, &mut markers)?;
+
+    let mut markers = markers
+        .into_iter()
+        .map(|(time, text, kind)| FcpxmlMarker {
+            time_code: TimeCode::from_duration(Duration::from_nanos(
+                (time - sequence_start).max(0) as u64,
+            ))
+            .unwrap_or_default(),
+            text,
+            kind,
+        })
+        .collect::<Vec<FcpxmlMarker>>();
+    markers.sort_by_key(|marker| marker.time_code);
+    Ok(markers)
+}
+
+/// Walks the children of `node`, whose local time `t` is at `t + shift` on
+/// the project timeline.
+fn collect_markers(
+    node: Node,
+    shift: i128,
+    markers: &mut Vec<(i128, String, MarkerKind)>,
+) -> Result<(), ParseFcpxmlError> {
+    for child in node.children().filter(|child| child.is_element()) {
+        let kind = match child.tag_name().name() {
+            "chapter-marker" => Some(MarkerKind::Chapter),
+            "marker" if child.has_attribute("completed") => Some(MarkerKind::ToDo),
+            "marker" => Some(MarkerKind::Standard),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            let start = parse_time(child.attribute("start").unwrap_or("0s"))?;
+            let text = child
+                .attribute("value")
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            markers.push((start + shift, text, kind));
+            continue;
+        }
+
+        let child_shift = match child.attribute("offset") {
+            Some(offset) => {
+                let start = parse_time(child.attribute("start").unwrap_or("0s"))?;
+                shift + parse_time(offset)? - start
+            }
+            None => shift,
+        };
+        collect_markers(child, child_shift, markers)?;
+    }
+    Ok(())
+}
+
+/// Parses an FCPXML rational time, like `3600/2400s` or `10s`, into nanoseconds.
+fn parse_time(text: &str) -> Result<i128, ParseFcpxmlError> {
+    let invalid = || ParseFcpxmlError::InvalidTime(text.to_string());
+    let seconds = text.strip_suffix('s').ok_or_else(invalid)?;
+    let (numerator, denominator) = seconds.split_once('/').unwrap_or((seconds, "1"));
+    let numerator = numerator.parse::<i128>().map_err(|_| invalid())?;
+    let denominator = denominator.parse::<i128>().map_err(|_| invalid())?;
+    if denominator == 0 {
+        return Err(invalid());
+    }
+    let nanoseconds = numerator.checked_mul(1_000_000_000).ok_or_else(invalid)?;
+    Ok(nanoseconds / denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FCPXML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE fcpxml>
+<fcpxml version="1.10">
+    <resources>
+        <format id="r1" frameDuration="1001/30000s" width="1920" height="1080"/>
+        <asset id="r2" name="Interview" start="0s" duration="7200s" hasVideo="1" hasAudio="1"/>
+    </resources>
+    <library>
+        <event name="Episode 1">
+            <project name="Episode 1">
+                <sequence format="r1" duration="5400s" tcStart="3600s" tcFormat="NDF">
+                    <spine>
+                        <asset-clip ref="r2" offset="3600s" name="Interview" start="1800s" duration="1800s">
+                            <chapter-marker start="1800s" duration="1001/30000s" value="Start" posterOffset="0s"/>
+                            <marker start="1870s" duration="1001/30000s" value="Introducing Bradley"/>
+                            <marker start="1900s" duration="1001/30000s" value="Fix audio here" completed="0"/>
+                            <asset-clip ref="r2" lane="1" offset="2000s" start="100s" duration="60s">
+                                <chapter-marker start="130s" duration="1001/30000s" value="B-roll of Rae"/>
+                            </asset-clip>
+                        </asset-clip>
+                        <gap offset="5400s" start="3600s" duration="1800s">
+                            <chapter-marker start="3630030/1000s" duration="1001/30000s" value="Links to share"/>
+                        </gap>
+                    </spine>
+                </sequence>
+            </project>
+        </event>
+    </library>
+</fcpxml>"#;
+
+    #[test]
+    fn places_markers_on_project_timeline() {
+        let markers = parse_fcpxml_markers(FCPXML).expect("parse_fcpxml_markers should succeed");
+
+        assert_eq!(
+            markers,
+            vec![
+                FcpxmlMarker {
+                    time_code: TimeCode::new(0, 0, 0).unwrap(),
+                    text: "Start".to_string(),
+                    kind: MarkerKind::Chapter,
+                },
+                FcpxmlMarker {
+                    time_code: TimeCode::new(0, 1, 10).unwrap(),
+                    text: "Introducing Bradley".to_string(),
+                    kind: MarkerKind::Standard,
+                },
+                FcpxmlMarker {
+                    time_code: TimeCode::new(0, 1, 40).unwrap(),
+                    text: "Fix audio here".to_string(),
+                    kind: MarkerKind::ToDo,
+                },
+                FcpxmlMarker {
+                    time_code: TimeCode::new(0, 3, 50).unwrap(),
+                    text: "B-roll of Rae".to_string(),
+                    kind: MarkerKind::Chapter,
+                },
+                FcpxmlMarker {
+                    time_code: TimeCode::with_milliseconds(0, 30, 30, 30).unwrap(),
+                    text: "Links to share".to_string(),
+                    kind: MarkerKind::Chapter,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_out_to_do_markers_by_default() {
+        let entries =
+            parse_fcpxml(FCPXML, &MarkerFilter::default()).expect("parse_fcpxml should succeed");
+
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| entry.text.as_str())
+                .collect::<Vec<&str>>(),
+            vec![
+                "Start",
+                "Introducing Bradley",
+                "B-roll of Rae",
+                "Links to share"
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_marker_kinds() {
+        let filter = "chapter".parse::<MarkerFilter>().unwrap();
+        let entries = parse_fcpxml(FCPXML, &filter).expect("parse_fcpxml should succeed");
+
+        assert_eq!(entries.len(), 3);
+        assert!("chapter,todo".parse::<MarkerFilter>().unwrap().to_do);
+        assert!("keyword".parse::<MarkerFilter>().is_err());
+    }
+
+    #[test]
+    fn parses_rational_times() {
+        assert_eq!(parse_time("0s").unwrap(), 0);
+        assert_eq!(parse_time("10s").unwrap(), 10_000_000_000);
+        assert_eq!(parse_time("3003/3000s").unwrap(), 1_001_000_000);
+        assert!(parse_time("10").is_err());
+        assert!(parse_time("1/0s").is_err());
+    }
+
+    #[test]
+    fn rejects_times_too_large_to_hold() {
+        let time = format!("{}/30000s", i128::MAX / 1000);
+        assert!(matches!(
+            parse_time(&time),
+            Err(ParseFcpxmlError::InvalidTime(text)) if text == time
+        ));
+    }
+
+    #[test]
+    fn errors_without_a_sequence() {
+        assert!(matches!(
+            parse_fcpxml_markers("<fcpxml version=\"1.10\"/>"),
+            Err(ParseFcpxmlError::MissingSequence)
+        ));
+    }
+}
@@ -0,0 +1,473 @@
+use std::time::Duration;
+
+use crate::types::{FrameRate, OutlineEntry, TimeCode};
+
+/// A problem with one line of a marker export.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseMarkersError {
+    /// The line number in the export, starting from 1.
+    pub line_number: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseMarkersError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line_number, self.message)
+    }
+}
+
+impl std::error::Error for ParseMarkersError {}
+
+fn error(line_number: usize, message: impl Into<String>) -> ParseMarkersError {
+    ParseMarkersError {
+        line_number,
+        message: message.into(),
+    }
+}
+
+/// Markers need a name to become outline entries, like the titles the text
+/// outline requires.
+fn unnamed_marker(line_number: usize, time_code: &TimeCode) -> ParseMarkersError {
+    error(
+        line_number,
+        format!("The marker at {} has no name", time_code),
+    )
+}
+
+/// Parses a marker EDL exported from a DaVinci Resolve timeline.
+///
+/// Each event's record in time is the marker position and the `|M:` note
+/// below it is the marker name. Resolve timelines start at 01:00:00:00 by
+/// default, so when every marker is past the first hour, that hour is taken
+/// off to make the outline start at 0:00.
+pub fn parse_resolve_edl(
+    edl: &str,
+    frame_rate: FrameRate,
+) -> Result<Vec<OutlineEntry>, ParseMarkersError> {
+    let mut frame_rate = frame_rate;
+    let mut entries: Vec<OutlineEntry> = vec![];
+    let mut event_lines = vec![];
+
+    for (index, line) in edl.lines().enumerate() {
+        let line_number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("TITLE:") {
+            continue;
+        }
+        if let Some(mode) = trimmed.strip_prefix("FCM:") {
+            if mode.trim() == "DROP FRAME" {
+                frame_rate = frame_rate.as_drop_frame().ok_or_else(|| {
+                    error(
+                        line_number,
+                        format!(
+                            "Drop-frame time codes are not defined at {} fps",
+                            frame_rate
+                        ),
+                    )
+                })?;
+            }
+            continue;
+        }
+        if trimmed.starts_with('|') {
+            let entry = entries
+                .last_mut()
+                .ok_or_else(|| error(line_number, "Marker note comes before any event"))?;
+            if let Some(name) = trimmed
+                .split('|')
+                .find_map(|field| field.trim().strip_prefix("M:"))
+            {
+                entry.text = name.trim().to_string();
+            }
+            continue;
+        }
+
+        let fields = trimmed.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() < 8 || !fields[0].chars().all(|c| c.is_ascii_digit()) {
+            return Err(error(
+                line_number,
+                format!("Invalid EDL event: {}", trimmed),
+            ));
+        }
+        let record_in = fields[fields.len() - 2];
+        let time_code = TimeCode::from_smpte(record_in, frame_rate)
+            .map_err(|e| error(line_number, e.to_string()))?;
+        entries.push(OutlineEntry {
+            time_code,
+            ..Default::default()
+        });
+        event_lines.push(line_number);
+    }
+    if let Some((line_number, entry)) = event_lines
+        .into_iter()
+        .zip(&entries)
+        .find(|(_, entry)| entry.text.is_empty())
+    {
+        return Err(unnamed_marker(line_number, &entry.time_code));
+    }
+
+    let hour = Duration::from_secs(3600);
+    if !entries.is_empty()
+        && entries
+            .iter()
+            .all(|entry| entry.time_code.to_duration() >= hour)
+    {
+        for entry in entries.iter_mut() {
+            entry.time_code = entry
+                .time_code
+                .checked_sub(hour)
+                .expect("Every marker is past the first hour");
+        }
+    }
+    Ok(entries)
+}
+
+/// Parses the marker list that Premiere Pro exports as CSV.
+///
+/// Premiere writes its markers as tab separated values despite the `.csv`
+/// extension, so both tabs and commas are accepted. The `In` column is the
+/// marker position and `Marker Name` is its text, falling back to the
+/// `Description` for unnamed markers.
+pub fn parse_premiere_csv(
+    csv: &str,
+    frame_rate: FrameRate,
+) -> Result<Vec<OutlineEntry>, ParseMarkersError> {
+    let mut rows = csv_rows(csv);
+    let (header_line, header) = rows
+        .next()
+        .ok_or_else(|| error(1, "The marker export is empty"))?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|field| field.eq_ignore_ascii_case(name))
+            .ok_or_else(|| error(header_line, format!("Missing the '{}' column", name)))
+    };
+    let name_column = column("Marker Name")?;
+    let description_column = column("Description").ok();
+    let in_column = column("In")?;
+
+    rows.map(|(line_number, fields)| {
+        let field = |column: usize| fields.get(column).map_or("", |field| field.trim());
+        let time_code = TimeCode::from_str_with_frame_rate(field(in_column), frame_rate)
+            .map_err(|e| error(line_number, e.to_string()))?;
+        let mut text = field(name_column);
+        if text.is_empty() {
+            text = description_column.map_or("", field);
+        }
+        if text.is_empty() {
+            return Err(unnamed_marker(line_number, &time_code));
+        }
+        Ok(OutlineEntry {
+            time_code,
+            text: text.to_string(),
+            ..Default::default()
+        })
+    })
+    .collect()
+}
+
+/// Parses an Audacity label track export, where each line holds the start and
+/// end of a label in seconds followed by its text, separated by tabs.
+///
+/// Lines starting with `\` hold the frequency range of spectral labels and are
+/// skipped.
+pub fn parse_audacity_labels(labels: &str) -> Result<Vec<OutlineEntry>, ParseMarkersError> {
+    let mut entries = vec![];
+    for (index, line) in labels.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() || line.starts_with('\\') {
+            continue;
+        }
+        let fields = line.splitn(3, '\t').collect::<Vec<&str>>();
+        let [start, _end, text] = fields[..] else {
+            return Err(error(
+                line_number,
+                format!("Expected start, end and label separated by tabs: {}", line),
+            ));
+        };
+        let seconds = start
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| error(line_number, format!("Invalid label start: {}", start)))?;
+        let time_code =
+            TimeCode::from_seconds(seconds).map_err(|e| error(line_number, e.to_string()))?;
+        if text.trim().is_empty() {
+            return Err(unnamed_marker(line_number, &time_code));
+        }
+        entries.push(OutlineEntry {
+            time_code,
+            text: text.trim().to_string(),
+            ..Default::default()
+        });
+    }
+    Ok(entries)
+}
+
+/// Parses markers and regions exported from Reaper's Region/Marker Manager.
+///
+/// Starts may be written as seconds, as `H:MM:SS.mmm` or `M:SS.mmm`, or as
+/// SMPTE time codes at `frame_rate`. Measures and beats can't be placed in
+/// time, so Reaper's time display needs to be set to one of those first.
+pub fn parse_reaper_csv(
+    csv: &str,
+    frame_rate: FrameRate,
+) -> Result<Vec<OutlineEntry>, ParseMarkersError> {
+    let mut rows = csv_rows(csv);
+    let (header_line, header) = rows
+        .next()
+        .ok_or_else(|| error(1, "The marker export is empty"))?;
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|field| field.eq_ignore_ascii_case(name))
+            .ok_or_else(|| error(header_line, format!("Missing the '{}' column", name)))
+    };
+    let name_column = column("Name")?;
+    let start_column = column("Start")?;
+
+    let mut entries = rows
+        .map(|(line_number, fields)| {
+            let field = |column: usize| fields.get(column).map_or("", |field| field.trim());
+            let start = field(start_column);
+            let time_code = match start.parse::<f64>() {
+                Ok(seconds) => TimeCode::from_seconds(seconds),
+                Err(_) => TimeCode::from_str_with_frame_rate(start, frame_rate),
+            }
+            .map_err(|_| {
+                error(
+                    line_number,
+                    format!(
+                        "Invalid start '{}', export with Reaper's time display set to minutes:seconds, seconds or timecode",
+                        start
+                    ),
+                )
+            })?;
+            let text = field(name_column);
+            if text.is_empty() {
+                return Err(unnamed_marker(line_number, &time_code));
+            }
+            Ok(OutlineEntry {
+                time_code,
+                text: text.to_string(),
+                ..Default::default()
+            })
+        })
+        .collect::<Result<Vec<OutlineEntry>, ParseMarkersError>>()?;
+    entries.sort();
+    Ok(entries)
+}
+
+/// Splits comma or tab separated values into rows of fields, along with the
+/// line number each row starts on.
+///
+/// The separator is taken from the first line. Fields starting with `"` are
+/// quoted, and can hold separators, line breaks and `""` for a quote.
+/// Blank lines are skipped.
+pub fn csv_rows(csv: &str) -> impl Iterator<Item = (usize, Vec<String>)> + '_ {
+    let csv = csv.trim_start_matches('\u{feff}');
+    let separator = if csv.lines().next().unwrap_or_default().contains('\t') {
+        '\t'
+    } else {
+        ','
+    };
+
+    let mut chars = csv.chars().peekable();
+    let mut line_number = 1;
+    std::iter::from_fn(move || loop {
+        chars.peek()?;
+        let row_line_number = line_number;
+        let mut fields = vec![];
+        let mut field = String::new();
+        let mut quoted = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' if quoted => quoted = false,
+                '"' if field.is_empty() => quoted = true,
+                '\n' if !quoted => {
+                    line_number += 1;
+                    break;
+                }
+                '\r' if !quoted => {}
+                c if c == separator && !quoted => fields.push(std::mem::take(&mut field)),
+                c => {
+                    if c == '\n' {
+                        line_number += 1;
+                    }
+                    field.push(c);
+                }
+            }
+        }
+        fields.push(field);
+        if fields.len() > 1 || !fields[0].trim().is_empty() {
+            return Some((row_line_number, fields));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(entries: &[OutlineEntry]) -> Vec<String> {
+        entries
+            .iter()
+            .map(|entry| format!("{} {}", entry.time_code, entry.text))
+            .collect()
+    }
+
+    #[test]
+    fn parses_resolve_marker_edl() {
+        let edl = "TITLE: Episode 1
+FCM: NON-DROP FRAME
+
+001  001      V     C        01:00:00:00 01:00:00:01 01:00:00:00 01:00:00:01
+ |C:ResolveColorBlue |M:Introduction |D:1
+
+002  001      V     C        01:01:10:15 01:01:10:16 01:01:10:15 01:01:10:16
+ |C:ResolveColorBlue |M:Background |D:1
+";
+        let entries =
+            parse_resolve_edl(edl, FrameRate::Fps30).expect("parse_resolve_edl should succeed");
+
+        assert_eq!(
+            texts(&entries),
+            vec!["0:00:00:00 Introduction", "0:01:10:15 Background"]
+        );
+    }
+
+    #[test]
+    fn reads_drop_frame_resolve_edl() {
+        let edl = "FCM: DROP FRAME
+001  001      V     C        00:10:00;00 00:10:00;01 00:10:00;00 00:10:00;01
+ |M:Demo |D:1
+";
+        let entries = parse_resolve_edl(edl, FrameRate::Fps29_97Ndf)
+            .expect("parse_resolve_edl should succeed");
+
+        assert_eq!(texts(&entries), vec!["9:59.999 Demo"]);
+        assert_eq!(
+            parse_resolve_edl("001 not an event", FrameRate::Fps30)
+                .unwrap_err()
+                .line_number,
+            1
+        );
+    }
+
+    #[test]
+    fn rejects_unnamed_markers() {
+        let edl = "001  001      V     C        00:00:00:00 00:00:00:01 00:00:00:00 00:00:00:01
+ |M:Introduction |D:1
+002  001      V     C        00:01:00:00 00:01:00:01 00:01:00:00 00:01:00:01
+ |C:ResolveColorBlue |D:1
+";
+        assert_eq!(
+            parse_resolve_edl(edl, FrameRate::Fps30).unwrap_err(),
+            ParseMarkersError {
+                line_number: 3,
+                message: "The marker at 0:01:00:00 has no name".to_string()
+            }
+        );
+        assert_eq!(
+            parse_premiere_csv("Marker Name,In\n,00:00:05:00\n", FrameRate::Fps30)
+                .unwrap_err()
+                .line_number,
+            2
+        );
+        assert_eq!(
+            parse_audacity_labels("0.0\t0.0\tIntro\n5.0\t5.0\t \n")
+                .unwrap_err()
+                .line_number,
+            2
+        );
+        assert_eq!(
+            parse_reaper_csv("#,Name,Start\nM1,,5.0\n", FrameRate::Fps30)
+                .unwrap_err()
+                .message,
+            "The marker at 0:05 has no name"
+        );
+    }
+
+    #[test]
+    fn parses_premiere_marker_csv() {
+        let csv = "\u{feff}Marker Name\tDescription\tIn\tOut\tDuration\tMarker Type
+Introduction\t\t00:00:00:00\t00:00:00:00\t00:00:00:00\tComment
+\tTalking about \"robots\"\t00:01:10:12\t00:01:10:12\t00:00:00:00\tChapter
+";
+        let entries =
+            parse_premiere_csv(csv, FrameRate::Fps25).expect("parse_premiere_csv should succeed");
+
+        assert_eq!(
+            texts(&entries),
+            vec![
+                "0:00:00:00@25 Introduction",
+                "0:01:10:12@25 Talking about \"robots\""
+            ]
+        );
+        assert!(parse_premiere_csv("Name,Start\n", FrameRate::Fps25).is_err());
+    }
+
+    #[test]
+    fn parses_audacity_labels() {
+        let labels = "0.000000\t0.000000\tIntroduction
+70.500000\t95.250000\tBackground
+\\\t200.000000\t4000.000000
+";
+        let entries = parse_audacity_labels(labels).expect("parse_audacity_labels should succeed");
+
+        assert_eq!(
+            texts(&entries),
+            vec!["0:00 Introduction", "1:10.500 Background"]
+        );
+        assert_eq!(
+            parse_audacity_labels("0.0 Introduction").unwrap_err(),
+            ParseMarkersError {
+                line_number: 1,
+                message: "Expected start, end and label separated by tabs: 0.0 Introduction"
+                    .to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn parses_reaper_markers_and_regions() {
+        let csv = "#,Name,Start,End,Length,Color
+R1,\"Background, part 1\",1:10.500,2:00.000,0:49.500,
+M1,Introduction,0:00.000,,,
+M2,Demo,125.25,,,
+";
+        let entries =
+            parse_reaper_csv(csv, FrameRate::Fps30).expect("parse_reaper_csv should succeed");
+
+        assert_eq!(
+            texts(&entries),
+            vec![
+                "0:00 Introduction",
+                "1:10.500 Background, part 1",
+                "2:05.250 Demo"
+            ]
+        );
+        assert_eq!(
+            parse_reaper_csv("#,Name,Start\nM1,Intro,1.1.00\n", FrameRate::Fps30)
+                .unwrap_err()
+                .line_number,
+            2
+        );
+    }
+
+    #[test]
+    fn splits_quoted_csv_fields() {
+        let rows = csv_rows("a,\"b, \"\"c\"\"\"\r\n\n\"multi\nline\",d\ne\n").collect::<Vec<_>>();
+
+        assert_eq!(
+            rows,
+            vec![
+                (1, vec!["a".to_string(), "b, \"c\"".to_string()]),
+                (3, vec!["multi\nline".to_string(), "d".to_string()]),
+                (5, vec!["e".to_string()]),
+            ]
+        );
+    }
+}
@@ -1,11 +1,14 @@
 use regex::Regex;
+use std::ops::Range;
+use std::sync::OnceLock;
 
-use crate::types::{OutlineEntry, TimeCode};
+use crate::types::{FrameRate, OutlineEntry, TimeCode};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum ParseOutlineError {
     InvalidTimeCode(String),
     InvalidOutlineEntry(String),
+    InvalidAttribute(String),
 }
 
 impl std::fmt::Display for ParseOutlineError {
@@ -17,42 +20,406 @@ impl std::fmt::Display for ParseOutlineError {
             ParseOutlineError::InvalidOutlineEntry(entry) => {
                 write!(f, "Invalid outline entry: {}", entry)
             }
+            ParseOutlineError::InvalidAttribute(attribute) => {
+                write!(f, "Invalid attribute: {}", attribute)
+            }
         }
     }
 }
 
 impl std::error::Error for ParseOutlineError {}
 
-pub fn parse_outline(outline: &str) -> Result<Vec<OutlineEntry>, ParseOutlineError> {
-    let mut entries = vec![];
+/// A problem with one line of an outline.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OutlineDiagnostic {
+    pub error: ParseOutlineError,
+    /// The line number in the outline, starting from 1.
+    pub line_number: usize,
+    /// The characters of the line that the error refers to, starting from 0.
+    pub columns: Range<usize>,
+    pub line: String,
+    pub suggestion: Option<String>,
+}
+
+impl std::fmt::Display for OutlineDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line_number,
+            self.columns.start + 1,
+            self.error
+        )?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " ({})", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Every problem found while parsing an outline.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ParseOutlineErrors {
+    pub diagnostics: Vec<OutlineDiagnostic>,
+}
+
+impl ParseOutlineErrors {
+    /// Renders the problems like compiler errors, with each offending line
+    /// printed and the problem underlined.
+    pub fn render(&self, source_name: &str) -> String {
+        let gutter_width = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.line_number.to_string().len())
+            .max()
+            .unwrap_or(1);
+        let gutter = " ".repeat(gutter_width);
+
+        let mut output = String::new();
+        for diagnostic in &self.diagnostics {
+            let line = diagnostic.line.replace('\t', "    ");
+            let before = display_width(diagnostic.line.chars().take(diagnostic.columns.start));
+            let underlined = display_width(
+                diagnostic
+                    .line
+                    .chars()
+                    .skip(diagnostic.columns.start)
+                    .take(diagnostic.columns.len()),
+            );
+
+            output += &format!("error: {}\n", diagnostic.error);
+            output += &format!(
+                "{}--> {}:{}:{}\n",
+                gutter,
+                source_name,
+                diagnostic.line_number,
+                diagnostic.columns.start + 1
+            );
+            output += &format!("{} |\n", gutter);
+            output += &format!(
+                "{:>width$} | {}\n",
+                diagnostic.line_number,
+                line,
+                width = gutter_width
+            );
+            output += &format!(
+                "{} | {}{}\n",
+                gutter,
+                " ".repeat(before),
+                "^".repeat(underlined.max(1))
+            );
+            if let Some(suggestion) = &diagnostic.suggestion {
+                output += &format!("{} = help: {}\n", gutter, suggestion);
+            }
+            output += "\n";
+        }
+        let count = self.diagnostics.len();
+        output += &format!(
+            "error: could not parse {} due to {} error{}\n",
+            source_name,
+            count,
+            if count == 1 { "" } else { "s" }
+        );
+        output
+    }
+}
+
+fn display_width(chars: impl Iterator<Item = char>) -> usize {
+    chars.map(|c| if c == '\t' { 4 } else { 1 }).sum()
+}
+
+impl std::fmt::Display for ParseOutlineErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render("outline"))
+    }
+}
+
+impl std::error::Error for ParseOutlineErrors {}
 
-    let outline_entry_regex =
-        Regex::new(r"([\d:]+) (.+)").expect("Outline entry capture regex is valid");
+pub fn parse_outline(outline: &str) -> Result<Vec<OutlineEntry>, ParseOutlineErrors> {
+    parse_outline_with_frame_rate(outline, TimeCode::DEFAULT_FRAME_RATE)
+}
+
+/// Parses an outline whose SMPTE time codes were exported at `frame_rate`.
+///
+/// SMPTE time codes are normalized to wall-clock time so chapters line up with
+/// transcript timestamps. Blank lines and lines starting with `#` are skipped,
+/// and every invalid line is reported rather than just the first.
+pub fn parse_outline_with_frame_rate(
+    outline: &str,
+    frame_rate: FrameRate,
+) -> Result<Vec<OutlineEntry>, ParseOutlineErrors> {
+    let mut entries: Vec<OutlineEntry> = vec![];
+    let mut diagnostics = vec![];
+    let mut indents: Vec<usize> = vec![];
+
+    for (index, line) in outline.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
 
-    for line in outline.lines() {
-        let captures = outline_entry_regex
-            .captures(line)
-            .ok_or(ParseOutlineError::InvalidOutlineEntry(line.to_string()))?;
-        let time_code = match TimeCode::from_str(&captures[1]) {
-            Ok(time_code) => time_code,
-            Err(e) => return Err(ParseOutlineError::InvalidTimeCode(e.to_string())),
+        let diagnostic = |error, bytes: Range<usize>, suggestion| OutlineDiagnostic {
+            error,
+            line_number: index + 1,
+            columns: line[..bytes.start].chars().count()..line[..bytes.end].chars().count(),
+            line: line.to_string(),
+            suggestion,
         };
-        // println!("time_code: {:?}", time_code);
-        // let time_code = time_code.map_err(|_| ParseOutlineError::InvalidTimeCode(captures[1].to_string()))?;
-        let text = &captures[2];
-        if text.is_empty() {
-            return Err(ParseOutlineError::InvalidOutlineEntry(line.to_string()));
+
+        let (level, entry_text) = get_nesting(line, &mut indents);
+        let offset = line.len() - entry_text.len();
+        let max_level = entries.last().map_or(0, |entry| entry.level + 1);
+        if level > max_level {
+            diagnostics.push(diagnostic(
+                ParseOutlineError::InvalidOutlineEntry(line.to_string()),
+                0..offset,
+                Some(format!(
+                    "entries can be nested at most one level below the entry before them, so this one can be at most {} level{} deep",
+                    max_level,
+                    if max_level == 1 { "" } else { "s" }
+                )),
+            ));
+            continue;
         }
 
-        entries.push(OutlineEntry {
+        let Some(captures) = outline_entry_regex().captures(entry_text) else {
+            diagnostics.push(diagnostic(
+                ParseOutlineError::InvalidOutlineEntry(line.to_string()),
+                offset..offset + entry_text.trim_end().len(),
+                Some(suggest_outline_entry(entry_text)),
+            ));
+            continue;
+        };
+        let time_code_match = captures.get(1).expect("Time code is captured");
+        let time_code =
+            match TimeCode::from_str_with_frame_rate(time_code_match.as_str(), frame_rate) {
+                Ok(time_code) => time_code,
+                Err(e) => {
+                    diagnostics.push(diagnostic(
+                        ParseOutlineError::InvalidTimeCode(e.to_string()),
+                        offset + time_code_match.start()..offset + time_code_match.end(),
+                        suggest_time_code(time_code_match.as_str()),
+                    ));
+                    continue;
+                }
+            };
+
+        let text_match = captures.get(2).expect("Entry text is captured");
+        let (text, attributes) = split_attributes(text_match.as_str());
+        let mut entry = OutlineEntry {
             time_code,
-            text: text.into(),
-        });
+            text: text.to_string(),
+            level,
+            ..Default::default()
+        };
+        let mut attributes_are_valid = true;
+        for (start, attribute) in attributes {
+            if let Err((error, suggestion)) = set_attribute(&mut entry, attribute) {
+                let start = offset + text_match.start() + start;
+                diagnostics.push(diagnostic(
+                    error,
+                    start..start + attribute.len(),
+                    Some(suggestion),
+                ));
+                attributes_are_valid = false;
+            }
+        }
+        if attributes_are_valid {
+            entries.push(entry);
+        }
     }
 
+    if !diagnostics.is_empty() {
+        return Err(ParseOutlineErrors { diagnostics });
+    }
     Ok(entries)
 }
 
+/// Matches the time code a line starts with, after its nesting, and the text
+/// after it.
+fn outline_entry_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"^([\d:;.,@]+) (.+)").expect("Outline entry capture regex is valid")
+    })
+}
+
+/// Matches each word after an entry's `|`, keeping quoted values, which can
+/// hold `\"` and `\\`, whole.
+fn attribute_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r#"[^\s=]+="(?:[^"\\]|\\.)*"|\S+"#).expect("Attribute regex is valid")
+    })
+}
+
+/// Attributes that are set by naming them, without a value.
+const FLAG_ATTRIBUTES: [&str; 1] = ["hidden"];
+
+/// Splits trailing `| key=value` attributes off an entry's text, returning
+/// the title and each attribute with its byte offset in `text`.
+///
+/// Text after a `|` is only read as attributes if every word in it contains
+/// `=` or is a flag like `hidden`, so titles like `Robots | Part 2` are left
+/// alone. Values with spaces or `|` can be quoted, like `notes="Ask about the
+/// demo"`, with `\"` for a quote. A `|` with nothing after it ends the title
+/// without adding attributes, so titles like `Robots | hidden` can be written
+/// as `Robots | hidden |`.
+pub(crate) fn split_attributes(text: &str) -> (&str, Vec<(usize, &str)>) {
+    let is_attribute = |word: &str| word.contains('=') || FLAG_ATTRIBUTES.contains(&word);
+    for (separator, _) in text.rmatch_indices('|') {
+        let start = separator + 1;
+        let words = attribute_regex()
+            .find_iter(&text[start..])
+            .collect::<Vec<_>>();
+        if words.iter().all(|word| is_attribute(word.as_str())) {
+            let attributes = words
+                .into_iter()
+                .map(|word| (start + word.start(), word.as_str()))
+                .collect();
+            return (text[..separator].trim_end(), attributes);
+        }
+    }
+    (text, vec![])
+}
+
+/// Writes `value` in quotes, with `\"` for each quote and `\\` for each
+/// backslash, so it can be read back as one attribute value.
+pub(crate) fn quote_attribute_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Reads a quoted attribute value, without its quotes, written by
+/// [`quote_attribute_value`].
+fn unquote_attribute_value(quoted: &str) -> String {
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => value.extend(chars.next()),
+            _ => value.push(c),
+        }
+    }
+    value
+}
+
+/// Sets an outline entry attribute, like `url=https://example.com` or
+/// `hidden`, or returns the error with a suggestion for fixing it.
+fn set_attribute(
+    entry: &mut OutlineEntry,
+    attribute: &str,
+) -> Result<(), (ParseOutlineError, String)> {
+    let invalid = |suggestion: String| {
+        Err((
+            ParseOutlineError::InvalidAttribute(attribute.to_string()),
+            suggestion,
+        ))
+    };
+    let Some((key, value)) = attribute.split_once('=') else {
+        match attribute {
+            "hidden" => entry.hidden = true,
+            _ => return invalid("flags can only be `hidden`".to_string()),
+        }
+        return Ok(());
+    };
+    let value = match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(quoted) => unquote_attribute_value(quoted),
+        None => value.to_string(),
+    };
+    let example = match key {
+        "notes" => "\"Ask about the demo\"",
+        "img" => "https://example.com/image.png",
+        _ => "https://example.com",
+    };
+    if value.is_empty() {
+        return invalid(format!(
+            "give `{}` a value, like `{}={}`",
+            key, key, example
+        ));
+    }
+    match key {
+        "url" => entry.url = Some(value),
+        "img" => entry.img = Some(value),
+        "notes" => entry.notes = Some(value),
+        "hidden" => {
+            return invalid("`hidden` doesn't take a value, write just `hidden`".to_string())
+        }
+        _ => return invalid("attributes can be `url`, `img`, `notes` or `hidden`".to_string()),
+    }
+    Ok(())
+}
+
+fn suggest_outline_entry(entry_text: &str) -> String {
+    if entry_text.trim().parse::<TimeCode>().is_ok() {
+        format!(
+            "add a title after the time code, like `{} Introduction`",
+            entry_text.trim()
+        )
+    } else {
+        "start the line with a time code, like `0:00 Introduction`, or comment it out with `#`"
+            .to_string()
+    }
+}
+
+/// Suggests the time code that was likely meant when minutes or seconds overflow.
+fn suggest_time_code(text: &str) -> Option<String> {
+    let groups = text
+        .split(':')
+        .map(|s| s.parse::<u32>())
+        .collect::<Result<Vec<u32>, _>>()
+        .ok()?;
+    let (minutes, seconds, time_code) = match groups[..] {
+        [hours, minutes, seconds] => (
+            minutes,
+            seconds,
+            TimeCode::normalized(hours, minutes, seconds),
+        ),
+        [minutes, seconds] => (minutes, seconds, TimeCode::normalized(0, minutes, seconds)),
+        _ => return None,
+    };
+    let unit = if minutes > 59 {
+        "minutes"
+    } else if seconds > 59 {
+        "seconds"
+    } else {
+        return None;
+    };
+    Some(format!(
+        "{} must be 0-59, did you mean {}?",
+        unit, time_code
+    ))
+}
+
+/// Finds how deeply a line is nested, returning the level and the rest of the line.
+///
+/// Nesting is either given by `-` or `--` prefixes, one dash per level, or by
+/// indenting a line further than the entry it belongs to. `indents` tracks the
+/// indentation of each open level between lines.
+fn get_nesting<'a>(line: &'a str, indents: &mut Vec<usize>) -> (usize, &'a str) {
+    let text = line.trim_start();
+
+    let dashes = text.chars().take_while(|c| *c == '-').count();
+    if dashes > 0 && text[dashes..].starts_with(char::is_whitespace) {
+        return (dashes, text[dashes..].trim_start());
+    }
+
+    let indent = line[..line.len() - text.len()]
+        .chars()
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum();
+    while indents.last().is_some_and(|last| indent < *last) {
+        indents.pop();
+    }
+    if indents.last().is_none_or(|last| indent > *last) {
+        indents.push(indent);
+    }
+    (indents.len() - 1, text)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,48 +432,60 @@ mod tests {
                     hours: 0,
                     minutes: 0,
                     seconds: 0,
+                    ..Default::default()
                 },
                 text: "Start".to_string(),
+                ..Default::default()
             },
             OutlineEntry {
                 time_code: TimeCode {
                     hours: 0,
                     minutes: 1,
                     seconds: 10,
+                    ..Default::default()
                 },
                 text: "Introducing Bradley and Luxonis".to_string(),
+                ..Default::default()
             },
             OutlineEntry {
                 time_code: TimeCode {
                     hours: 0,
                     minutes: 17,
                     seconds: 24,
+                    ..Default::default()
                 },
                 text: "Introducing Rae robot".to_string(),
+                ..Default::default()
             },
             OutlineEntry {
                 time_code: TimeCode {
                     hours: 0,
                     minutes: 53,
                     seconds: 44,
+                    ..Default::default()
                 },
                 text: "How RobotHub works".to_string(),
+                ..Default::default()
             },
             OutlineEntry {
                 time_code: TimeCode {
                     hours: 1,
                     minutes: 4,
                     seconds: 13,
+                    ..Default::default()
                 },
                 text: "Security on RobotHub".to_string(),
+                ..Default::default()
             },
             OutlineEntry {
                 time_code: TimeCode {
                     hours: 1,
                     minutes: 31,
                     seconds: 59,
+                    ..Default::default()
                 },
                 text: "Links to share".to_string(),
+                ..Default::default()
             },
         ];
 
@@ -131,14 +510,71 @@ mod tests {
         }
     }
 
+    #[test]
+    fn nests_indented_entries() {
+        let outline_text = "0:00 Start\n  1:10 Intro\n    1:30 Background\n  5:00 Company\n10:00 Demo\n\t11:00 Setup";
+        let entries = parse_outline(outline_text).expect("parse_outline should succeed");
+
+        let levels = entries.iter().map(|entry| entry.level).collect::<Vec<_>>();
+        assert_eq!(levels, vec![0, 1, 2, 1, 0, 1]);
+        assert_eq!(entries[2].text, "Background");
+    }
+
+    #[test]
+    fn nests_dash_prefixed_entries() {
+        let outline_text =
+            "0:00 Start\n- 1:10 Intro\n-- 1:30 Background\n- 5:00 Company\n10:00 Demo";
+        let entries = parse_outline(outline_text).expect("parse_outline should succeed");
+
+        let levels = entries.iter().map(|entry| entry.level).collect::<Vec<_>>();
+        assert_eq!(levels, vec![0, 1, 2, 1, 0]);
+        assert_eq!(entries[1].text, "Intro");
+    }
+
+    #[test]
+    fn error_for_entry_without_parent() {
+        let result = parse_outline("0:00 Start\n-- 1:30 Background");
+
+        assert!(matches!(
+            result.unwrap_err().diagnostics[0].error,
+            ParseOutlineError::InvalidOutlineEntry(_)
+        ));
+    }
+
+    #[test]
+    fn normalizes_drop_frame_time_codes() {
+        let outline_text = "00:00:00;00 Start\n01:00:00;02 An hour in";
+        let entries = parse_outline_with_frame_rate(outline_text, FrameRate::Fps29_97Df)
+            .expect("parse_outline_with_frame_rate should succeed");
+
+        assert_eq!(entries[0].time_code, TimeCode::new(0, 0, 0).unwrap());
+        assert_eq!(
+            entries[1].time_code,
+            TimeCode::with_milliseconds(1, 0, 0, 63).unwrap()
+        );
+        assert_eq!(entries[1].text, "An hour in");
+    }
+
+    #[test]
+    fn normalizes_non_drop_frame_time_codes() {
+        let outline_text = "01:00:00:00 An hour of frames";
+        let entries = parse_outline_with_frame_rate(outline_text, FrameRate::Fps29_97Ndf)
+            .expect("parse_outline_with_frame_rate should succeed");
+
+        assert_eq!(
+            entries[0].time_code,
+            TimeCode::with_milliseconds(1, 0, 3, 600).unwrap()
+        );
+    }
+
     #[test]
     fn error_for_invalid_minutes() {
         let outline_text = "00:60:00 Introducing Bradley and Luxonis";
-        let result = parse_outline(&outline_text);
+        let result = parse_outline(outline_text);
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().diagnostics[0].error,
             ParseOutlineError::InvalidTimeCode(_)
         ));
     }
@@ -146,11 +582,11 @@ mod tests {
     #[test]
     fn error_for_invalid_seconds() {
         let outline_text = "00:01:60 Introducing Bradley and Luxonis";
-        let result = parse_outline(&outline_text);
+        let result = parse_outline(outline_text);
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().diagnostics[0].error,
             ParseOutlineError::InvalidTimeCode(_)
         ));
     }
@@ -158,12 +594,126 @@ mod tests {
     #[test]
     fn errors_for_no_text() {
         let outline_text = "00:01:00 ";
-        let result = parse_outline(&outline_text);
+        let result = parse_outline(outline_text);
 
         assert!(result.is_err());
         assert!(matches!(
-            result.unwrap_err(),
+            result.unwrap_err().diagnostics[0].error,
             ParseOutlineError::InvalidOutlineEntry(_)
         ));
     }
+
+    #[test]
+    fn skips_blank_lines_and_comments() {
+        let outline_text = "# Episode 12\n\n0:00 Start\n\n  # TODO: check this one\n1:10 Intro\n";
+        let entries = parse_outline(outline_text).expect("parse_outline should succeed");
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].text, "Intro");
+    }
+
+    #[test]
+    fn reports_every_error_with_its_location() {
+        let outline_text = "Chapters\n0:00 Start\n\n00:60:05 Introducing Bradley\n1:01:61 Links\n";
+        let diagnostics = parse_outline(outline_text).unwrap_err().diagnostics;
+
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0].line_number, 1);
+        assert_eq!(diagnostics[0].columns, 0..8);
+        assert_eq!(diagnostics[1].line_number, 4);
+        assert_eq!(diagnostics[1].columns, 0..8);
+        assert_eq!(
+            diagnostics[1].suggestion.as_deref(),
+            Some("minutes must be 0-59, did you mean 1:00:05?")
+        );
+        assert_eq!(diagnostics[2].line_number, 5);
+        assert_eq!(
+            diagnostics[2].suggestion.as_deref(),
+            Some("seconds must be 0-59, did you mean 1:02:01?")
+        );
+    }
+
+    #[test]
+    fn reports_headers_with_a_time_code_in_them() {
+        let diagnostics = parse_outline("Show notes 1:00 foo\n0:00 Start\n- 1:00 Foo\n")
+            .unwrap_err()
+            .diagnostics;
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line_number, 1);
+        assert_eq!(diagnostics[0].columns, 0..19);
+        assert_eq!(
+            diagnostics[0].suggestion.as_deref(),
+            Some("start the line with a time code, like `0:00 Introduction`, or comment it out with `#`")
+        );
+    }
+
+    #[test]
+    fn reads_entry_attributes() {
+        let outline_text = "0:00 Start | url=https://example.com img=https://example.com/a.png\n1:10 Robots | Part 2\n";
+        let entries = parse_outline(outline_text).expect("parse_outline should succeed");
+
+        assert_eq!(entries[0].text, "Start");
+        assert_eq!(entries[0].url.as_deref(), Some("https://example.com"));
+        assert_eq!(entries[0].img.as_deref(), Some("https://example.com/a.png"));
+        assert_eq!(entries[1].text, "Robots | Part 2");
+        assert_eq!(entries[1].url, None);
+    }
+
+    #[test]
+    fn reads_hidden_flag_and_quoted_notes() {
+        let outline_text =
+            "0:00 Start | hidden notes=\"Ask about the demo\"\n1:10 Robots | notes=Short\n";
+        let entries = parse_outline(outline_text).expect("parse_outline should succeed");
+
+        assert_eq!(entries[0].text, "Start");
+        assert!(entries[0].hidden);
+        assert_eq!(entries[0].notes.as_deref(), Some("Ask about the demo"));
+        assert!(!entries[1].hidden);
+        assert_eq!(entries[1].notes.as_deref(), Some("Short"));
+    }
+
+    #[test]
+    fn error_for_unknown_attribute() {
+        let diagnostics = parse_outline("0:00 Start | link=https://example.com")
+            .unwrap_err()
+            .diagnostics;
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].error,
+            ParseOutlineError::InvalidAttribute("link=https://example.com".to_string())
+        );
+        assert_eq!(diagnostics[0].columns, 13..37);
+        assert_eq!(
+            diagnostics[0].suggestion.as_deref(),
+            Some("attributes can be `url`, `img`, `notes` or `hidden`")
+        );
+        assert_eq!(
+            set_attribute(&mut OutlineEntry::default(), "pinned"),
+            Err((
+                ParseOutlineError::InvalidAttribute("pinned".to_string()),
+                "flags can only be `hidden`".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn renders_errors_like_a_compiler() {
+        let outline_text = "0:00 Start\n  00:60:05 Introducing Bradley";
+        let errors = parse_outline(outline_text).unwrap_err();
+
+        assert_eq!(
+            errors.render("outline.txt"),
+            r#"error: Invalid time code: Minutes must be between 0-59
+ --> outline.txt:2:3
+  |
+2 |   00:60:05 Introducing Bradley
+  |   ^^^^^^^^
+  = help: minutes must be 0-59, did you mean 1:00:05?
+
+error: could not parse outline.txt due to 1 error
+"#
+        );
+    }
 }
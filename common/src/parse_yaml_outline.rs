@@ -0,0 +1,151 @@
+use serde::Deserialize;
+
+use crate::types::{FrameRate, OutlineEntry, TimeCode};
+
+#[derive(Debug)]
+pub enum ParseYamlOutlineError {
+    Yaml(serde_yaml::Error),
+    InvalidTimeCode { title: String, message: String },
+}
+
+impl std::fmt::Display for ParseYamlOutlineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseYamlOutlineError::Yaml(e) => write!(f, "Invalid YAML outline: {}", e),
+            ParseYamlOutlineError::InvalidTimeCode { title, message } => {
+                write!(f, "Invalid time code for '{}': {}", title, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseYamlOutlineError {}
+
+/// One chapter of a YAML outline, with its sub-topics under `chapters`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct YamlOutlineEntry {
+    #[serde(alias = "time_code")]
+    time: YamlTime,
+    #[serde(alias = "text")]
+    title: String,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    img: Option<String>,
+    #[serde(default)]
+    hidden: bool,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    chapters: Vec<YamlOutlineEntry>,
+}
+
+/// A time written as a time code, like `1:10`, or as seconds, like `70.5`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum YamlTime {
+    Seconds(f64),
+    TimeCode(String),
+}
+
+/// Parses an outline written as a YAML list of chapters, for outlines with
+/// more metadata than fits comfortably on one line.
+///
+/// ```yaml
+/// - time: 0:00
+///   title: Introduction
+/// - time: 1:10
+///   title: Robots
+///   url: https://example.com
+///   notes: Ask about the demo
+///   chapters:
+///     - time: 2:00
+///       title: First robot
+///       hidden: true
+/// ```
+pub fn parse_yaml_outline(
+    yaml: &str,
+    frame_rate: FrameRate,
+) -> Result<Vec<OutlineEntry>, ParseYamlOutlineError> {
+    let chapters = match yaml.trim() {
+        "" => vec![],
+        _ => serde_yaml::from_str::<Vec<YamlOutlineEntry>>(yaml)
+            .map_err(ParseYamlOutlineError::Yaml)?,
+    };
+    let mut entries = vec![];
+    add_entries(&mut entries, chapters, 0, frame_rate)?;
+    Ok(entries)
+}
+
+fn add_entries(
+    entries: &mut Vec<OutlineEntry>,
+    chapters: Vec<YamlOutlineEntry>,
+    level: usize,
+    frame_rate: FrameRate,
+) -> Result<(), ParseYamlOutlineError> {
+    for chapter in chapters {
+        let time_code = match &chapter.time {
+            YamlTime::Seconds(seconds) => TimeCode::from_seconds(*seconds),
+            YamlTime::TimeCode(text) => TimeCode::from_str_with_frame_rate(text, frame_rate),
+        }
+        .map_err(|e| ParseYamlOutlineError::InvalidTimeCode {
+            title: chapter.title.clone(),
+            message: e.to_string(),
+        })?;
+        entries.push(OutlineEntry {
+            time_code,
+            text: chapter.title,
+            level,
+            url: chapter.url,
+            img: chapter.img,
+            hidden: chapter.hidden,
+            notes: chapter.notes,
+        });
+        add_entries(entries, chapter.chapters, level + 1, frame_rate)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_outline;
+
+    #[test]
+    fn parses_nested_yaml_outline() {
+        let yaml = r#"
+- time: 0:00
+  title: Start
+  url: https://example.com
+- time: 70.5
+  title: Robots
+  notes: Ask about the demo
+  chapters:
+    - time: "2:00"
+      title: First robot
+      hidden: true
+"#;
+        let expected = parse_outline(
+            "0:00 Start | url=https://example.com\n1:10.5 Robots | notes=\"Ask about the demo\"\n  2:00 First robot | hidden\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            parse_yaml_outline(yaml, TimeCode::DEFAULT_FRAME_RATE).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn error_for_invalid_yaml_time_code() {
+        let error =
+            parse_yaml_outline("- time: 0:75\n  title: Start", TimeCode::DEFAULT_FRAME_RATE)
+                .unwrap_err();
+
+        assert!(matches!(
+            error,
+            ParseYamlOutlineError::InvalidTimeCode { ref title, .. } if title == "Start"
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::OutlineEntry;
+
+/// A Podcasting 2.0 chapters file, as linked from a `<podcast:chapters>` tag.
+///
+/// See <https://github.com/Podcastindex-org/podcast-namespace/blob/main/docs/examples/chapters/jsonChapters.md>.
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PodcastChapters {
+    pub version: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub podcast_name: Option<String>,
+    pub chapters: Vec<PodcastChapter>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PodcastChapter {
+    /// Seconds from the start of the episode.
+    pub start_time: f64,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub img: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Whether the chapter is listed in the table of contents. Apps treat a
+    /// missing value as `true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toc: Option<bool>,
+}
+
+impl PodcastChapters {
+    pub const VERSION: &'static str = "1.2.0";
+
+    /// Converts an outline into chapters, in time order.
+    ///
+    /// Nested and hidden entries become chapters that are left out of the
+    /// table of contents, so apps still show their titles and images as they
+    /// play.
+    pub fn from_outline(entries: &[OutlineEntry]) -> PodcastChapters {
+        let mut entries = entries.to_vec();
+        entries.sort();
+        PodcastChapters {
+            version: PodcastChapters::VERSION.to_string(),
+            title: None,
+            podcast_name: None,
+            chapters: entries
+                .into_iter()
+                .map(|entry| PodcastChapter {
+                    start_time: (entry.time_code.to_seconds() * 1000.0).round() / 1000.0,
+                    title: entry.text,
+                    img: entry.img,
+                    url: entry.url,
+                    toc: (entry.level > 0 || entry.hidden).then_some(false),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_outline;
+
+    #[test]
+    fn converts_outline_to_chapters_json() {
+        let entries = parse_outline(
+            "0:00 Start | url=https://example.com\n1:10.5 Robots | img=https://example.com/rae.png\n  2:00 Rae\n",
+        )
+        .expect("parse_outline should succeed");
+        let mut chapters = PodcastChapters::from_outline(&entries);
+        chapters.title = Some("Episode 1".to_string());
+
+        assert_eq!(
+            chapters.to_json().unwrap(),
+            r#"{
+  "version": "1.2.0",
+  "title": "Episode 1",
+  "chapters": [
+    {
+      "startTime": 0.0,
+      "title": "Start",
+      "url": "https://example.com"
+    },
+    {
+      "startTime": 70.5,
+      "title": "Robots",
+      "img": "https://example.com/rae.png"
+    },
+    {
+      "startTime": 120.0,
+      "title": "Rae",
+      "toc": false
+    }
+  ]
+}"#
+        );
+    }
+}
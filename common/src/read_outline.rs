@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::ErrorKind;
+use crate::outline_source::{OutlineFormat, OutlineOptions, OutlineSourceError};
+use crate::types::OutlineEntry;
+
+#[derive(Debug)]
+pub enum ReadOutlineError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, OutlineSourceError),
+}
+
+impl std::fmt::Display for ReadOutlineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadOutlineError::Io(path, e) => {
+                write!(f, "Could not read outline {}: {}", path.display(), e)
+            }
+            ReadOutlineError::Parse(path, OutlineSourceError::Outline(errors)) => {
+                write!(f, "{}", errors.render(&path.display().to_string()))
+            }
+            ReadOutlineError::Parse(path, e) => write!(f, "{}: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for ReadOutlineError {}
+
+impl ReadOutlineError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            ReadOutlineError::Io(..) => ErrorKind::Io,
+            ReadOutlineError::Parse(..) => ErrorKind::Outline,
+        }
+    }
+}
+
+/// Reads an outline from a text file, an editor's marker export, or a Final
+/// Cut Pro `.fcpxmld` bundle.
+///
+/// The format is detected from the extension and contents of the file unless
+/// `options` sets one.
+pub fn read_outline(
+    path: &Path,
+    options: &OutlineOptions,
+) -> Result<Vec<OutlineEntry>, ReadOutlineError> {
+    read_outline_with_format(path, options).map(|(entries, _)| entries)
+}
+
+/// Reads an outline like `read_outline`, also returning the format it was
+/// read as.
+pub fn read_outline_with_format(
+    path: &Path,
+    options: &OutlineOptions,
+) -> Result<(Vec<OutlineEntry>, OutlineFormat), ReadOutlineError> {
+    let contents = if path.is_dir() {
+        read_to_string(&path.join("Info.fcpxml"))?
+    } else {
+        read_to_string(path)?
+    };
+    let format = options
+        .format
+        .unwrap_or_else(|| OutlineFormat::detect(path, &contents));
+    let entries = format
+        .source()
+        .parse(&contents, options)
+        .map_err(|e| ReadOutlineError::Parse(path.to_path_buf(), e))?;
+    Ok((entries, format))
+}
+
+/// Reads a file as UTF-8, or as UTF-16 if it starts with a UTF-16 byte order
+/// mark, as Premiere's marker exports do.
+fn read_to_string(path: &Path) -> Result<String, ReadOutlineError> {
+    let bytes = std::fs::read(path).map_err(|e| ReadOutlineError::Io(path.to_path_buf(), e))?;
+    let invalid_data = |message: &str| {
+        ReadOutlineError::Io(
+            path.to_path_buf(),
+            std::io::Error::new(std::io::ErrorKind::InvalidData, message),
+        )
+    };
+    let (rest, big_endian) = match bytes.as_slice() {
+        [0xFF, 0xFE, rest @ ..] => (rest, false),
+        [0xFE, 0xFF, rest @ ..] => (rest, true),
+        bytes => {
+            return std::str::from_utf8(bytes)
+                .map(str::to_string)
+                .map_err(|_| invalid_data("stream did not contain valid UTF-8"))
+        }
+    };
+    let units = rest
+        .chunks_exact(2)
+        .map(|pair| match big_endian {
+            true => u16::from_be_bytes([pair[0], pair[1]]),
+            false => u16::from_le_bytes([pair[0], pair[1]]),
+        })
+        .collect::<Vec<u16>>();
+    String::from_utf16(&units).map_err(|_| invalid_data("stream did not contain valid UTF-16"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_utf_16_marker_exports() {
+        let path = std::env::temp_dir().join("read_outline_utf_16_markers.csv");
+        let text = "Marker Name\tDescription\tIn\nIntroduction\t\t00:00:00:00\n";
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        std::fs::write(&path, bytes).unwrap();
+
+        let (entries, format) = read_outline_with_format(&path, &OutlineOptions::default())
+            .expect("read_outline_with_format should succeed");
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(format, OutlineFormat::PremiereCsv);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].text, "Introduction");
+    }
+}
@@ -0,0 +1,228 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::types::{OutlineEntry, TimeCode};
+
+/// A change made to the episode after the outline and transcript were exported.
+///
+/// Positions refer to the timeline the outline and transcript were exported
+/// from, so a list of edits can be written down without accounting for the
+/// edits that come before them.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EditOperation {
+    /// `length` of new material added at `at`, e.g. `insert 0:45 at 12:30`.
+    Insert { at: TimeCode, length: Duration },
+    /// Material between `start` and `end` removed, e.g. `cut 3:10-3:55`.
+    Cut { start: TimeCode, end: TimeCode },
+}
+
+impl FromStr for EditOperation {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<EditOperation, Self::Err> {
+        let parse_time_code = |text: &str| {
+            text.parse::<TimeCode>()
+                .map_err(|e| format!("Invalid time code '{}': {}", text, e))
+        };
+        let words = text.split_whitespace().collect::<Vec<&str>>();
+        match words.as_slice() {
+            [operation, length, at_keyword, at]
+                if operation.eq_ignore_ascii_case("insert")
+                    && at_keyword.eq_ignore_ascii_case("at") =>
+            {
+                Ok(EditOperation::Insert {
+                    at: parse_time_code(at)?,
+                    length: parse_time_code(length)?.to_duration(),
+                })
+            }
+            [operation, range] if operation.eq_ignore_ascii_case("cut") => {
+                let (start, end) = range
+                    .split_once('-')
+                    .ok_or(format!("Cut range must look like 3:10-3:55: {}", range))?;
+                let (start, end) = (parse_time_code(start)?, parse_time_code(end)?);
+                if start >= end {
+                    return Err(format!("Cut must end after it starts: {}", range));
+                }
+                Ok(EditOperation::Cut { start, end })
+            }
+            _ => Err(format!(
+                "Invalid edit '{}', expected 'insert <length> at <time>' or 'cut <start>-<end>'",
+                text
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for EditOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditOperation::Insert { at, length } => {
+                let length = TimeCode::from_duration(*length).unwrap_or_default();
+                write!(f, "insert {} at {}", length, at)
+            }
+            EditOperation::Cut { start, end } => write!(f, "cut {}-{}", start, end),
+        }
+    }
+}
+
+/// Where a time code ends up after a list of edits.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ShiftedTimeCode {
+    pub time_code: TimeCode,
+    /// The cut the time code fell inside of, if any. Such time codes are moved
+    /// to where the cut now starts.
+    pub inside_cut: Option<EditOperation>,
+}
+
+pub fn shift_time_code(time_code: &TimeCode, edits: &[EditOperation]) -> ShiftedTimeCode {
+    let mut added = Duration::ZERO;
+    let mut removed = Duration::ZERO;
+    let mut inside_cut = None;
+    for edit in edits {
+        match *edit {
+            EditOperation::Insert { at, length } if at <= *time_code => added += length,
+            EditOperation::Cut { start, end } if end <= *time_code => removed += end - start,
+            EditOperation::Cut { start, .. } if start <= *time_code => {
+                removed += *time_code - start;
+                inside_cut = Some(*edit);
+            }
+            _ => {}
+        }
+    }
+
+    let time_code = time_code.saturating_add(added).saturating_sub(removed);
+    ShiftedTimeCode {
+        time_code,
+        inside_cut,
+    }
+}
+
+/// A chapter that started inside a cut.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShiftWarning {
+    pub entry: OutlineEntry,
+    pub cut: EditOperation,
+    pub moved_to: TimeCode,
+}
+
+impl std::fmt::Display for ShiftWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Chapter '{}' at {} is inside '{}' and was moved to {}",
+            self.entry.text, self.entry.time_code, self.cut, self.moved_to
+        )
+    }
+}
+
+/// Moves every outline entry to where its content is after `edits`.
+pub fn shift_outline(
+    outline_entries: &[OutlineEntry],
+    edits: &[EditOperation],
+) -> (Vec<OutlineEntry>, Vec<ShiftWarning>) {
+    let mut warnings = vec![];
+    let entries = outline_entries
+        .iter()
+        .map(|entry| {
+            let shifted = shift_time_code(&entry.time_code, edits);
+            if let Some(cut) = shifted.inside_cut {
+                warnings.push(ShiftWarning {
+                    entry: entry.clone(),
+                    cut,
+                    moved_to: shifted.time_code,
+                });
+            }
+            OutlineEntry {
+                time_code: shifted.time_code,
+                ..entry.clone()
+            }
+        })
+        .collect();
+    (entries, warnings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time_code(text: &str) -> TimeCode {
+        text.parse().expect("time code is valid")
+    }
+
+    #[test]
+    fn parses_edit_operations() {
+        assert_eq!(
+            "insert 0:45 at 12:30".parse::<EditOperation>().unwrap(),
+            EditOperation::Insert {
+                at: time_code("12:30"),
+                length: Duration::from_secs(45),
+            }
+        );
+        assert_eq!(
+            "Cut 3:10-3:55".parse::<EditOperation>().unwrap(),
+            EditOperation::Cut {
+                start: time_code("3:10"),
+                end: time_code("3:55"),
+            }
+        );
+        assert!("cut 3:55-3:10".parse::<EditOperation>().is_err());
+        assert!("insert 0:45 12:30".parse::<EditOperation>().is_err());
+        assert!("trim 0:45".parse::<EditOperation>().is_err());
+    }
+
+    #[test]
+    fn displays_edit_operations() {
+        for text in ["insert 0:45 at 12:30", "cut 3:10-3:55"] {
+            assert_eq!(text.parse::<EditOperation>().unwrap().to_string(), text);
+        }
+    }
+
+    #[test]
+    fn shifts_time_codes_after_edits() {
+        let edits = [
+            "insert 0:45 at 12:30".parse().unwrap(),
+            "cut 3:10-3:55".parse().unwrap(),
+        ];
+
+        let shift = |text: &str| shift_time_code(&time_code(text), &edits).time_code;
+        assert_eq!(shift("1:00"), time_code("1:00"));
+        assert_eq!(shift("3:55"), time_code("3:10"));
+        assert_eq!(shift("12:29"), time_code("11:44"));
+        assert_eq!(shift("12:30"), time_code("12:30"));
+        assert_eq!(shift("1:00:00"), time_code("1:00:00"));
+    }
+
+    #[test]
+    fn warns_about_chapters_inside_cuts() {
+        let outline_entries = vec![
+            OutlineEntry {
+                time_code: time_code("0:00"),
+                text: "Start".to_string(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: time_code("3:20"),
+                text: "Cut topic".to_string(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: time_code("5:00"),
+                text: "Kept topic".to_string(),
+                ..Default::default()
+            },
+        ];
+        let edits = ["cut 3:10-3:55".parse().unwrap()];
+
+        let (entries, warnings) = shift_outline(&outline_entries, &edits);
+
+        assert_eq!(entries[0].time_code, time_code("0:00"));
+        assert_eq!(entries[1].time_code, time_code("3:10"));
+        assert_eq!(entries[2].time_code, time_code("4:15"));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].entry.text, "Cut topic");
+        assert_eq!(
+            warnings[0].to_string(),
+            "Chapter 'Cut topic' at 3:20 is inside 'cut 3:10-3:55' and was moved to 3:10"
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// The rules a Markdown renderer uses to turn a heading into the anchor that
+/// links to it.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum SlugStyle {
+    /// GitHub and GitLab, and site generators that use `github-slugger`,
+    /// like Docusaurus and Hugo. Keeps Unicode letters, numbers, underscores
+    /// and hyphens, and turns each space into a hyphen.
+    #[default]
+    GitHub,
+    /// Pandoc, Quarto and R Markdown. Also keeps periods, turns runs of
+    /// whitespace into one hyphen, and drops everything before the first
+    /// letter.
+    Pandoc,
+    /// Kramdown, which Jekyll uses. Keeps only ASCII letters, numbers and
+    /// hyphens, and drops everything before the first letter.
+    Kramdown,
+}
+
+impl SlugStyle {
+    /// The slug for `text`, without making it unique.
+    pub fn slugify(&self, text: &str) -> String {
+        let text = text.trim().to_lowercase();
+        match self {
+            SlugStyle::GitHub => text
+                .chars()
+                .filter(|c| c.is_alphanumeric() || ['_', '-', ' '].contains(c))
+                .map(|c| if c == ' ' { '-' } else { c })
+                .collect(),
+            SlugStyle::Pandoc => {
+                let slug = text
+                    .chars()
+                    .filter(|c| {
+                        c.is_alphanumeric() || c.is_whitespace() || ['_', '-', '.'].contains(c)
+                    })
+                    .collect::<String>()
+                    .split_whitespace()
+                    .collect::<Vec<&str>>()
+                    .join("-");
+                or_section(slug.trim_start_matches(|c: char| !c.is_alphabetic()))
+            }
+            SlugStyle::Kramdown => {
+                let slug = text
+                    .trim_start_matches(|c: char| !c.is_ascii_alphabetic())
+                    .chars()
+                    .filter(|c| c.is_ascii_alphanumeric() || ['-', ' '].contains(c))
+                    .map(|c| if c == ' ' { '-' } else { c })
+                    .collect::<String>();
+                or_section(&slug)
+            }
+        }
+    }
+}
+
+/// Pandoc and Kramdown use `section` for headings without a letter.
+fn or_section(slug: &str) -> String {
+    if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+impl FromStr for SlugStyle {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<SlugStyle, Self::Err> {
+        match text.to_lowercase().as_str() {
+            "github" | "gitlab" | "hugo" | "docusaurus" => Ok(SlugStyle::GitHub),
+            "pandoc" | "quarto" => Ok(SlugStyle::Pandoc),
+            "kramdown" | "jekyll" => Ok(SlugStyle::Kramdown),
+            _ => Err(format!(
+                "Unknown slug style '{}', expected github, pandoc or kramdown",
+                text
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for SlugStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SlugStyle::GitHub => "github",
+            SlugStyle::Pandoc => "pandoc",
+            SlugStyle::Kramdown => "kramdown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The slug for `text` in GitHub's style, like `1-hello-world`.
+pub fn slugify(text: &str) -> String {
+    SlugStyle::GitHub.slugify(text)
+}
+
+/// Makes slugs for the headings of a document in order, adding `-1`, `-2`
+/// and so on to repeated headings so that each anchor is unique.
+#[derive(Debug, Default)]
+pub struct Slugger {
+    style: SlugStyle,
+    occurrences: HashMap<String, usize>,
+}
+
+impl Slugger {
+    pub fn new(style: SlugStyle) -> Slugger {
+        Slugger {
+            style,
+            occurrences: HashMap::new(),
+        }
+    }
+
+    /// The slug for the next heading, `text`.
+    pub fn slug(&mut self, text: &str) -> String {
+        let original = self.style.slugify(text);
+        let mut slug = original.clone();
+        while self.occurrences.contains_key(&slug) {
+            let count = self
+                .occurrences
+                .get_mut(&original)
+                .expect("The original slug was seen");
+            *count += 1;
+            slug = format!("{}-{}", original, count);
+        }
+        self.occurrences.insert(slug.clone(), 0);
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugifies_like_github() {
+        assert_eq!(slugify("Start"), "start");
+        assert_eq!(
+            slugify("Introducing Bradley + Luxonis"),
+            "introducing-bradley--luxonis"
+        );
+        assert_eq!(
+            slugify("Special characters!@#$%^&*()_+[]~><👍🤖"),
+            "special-characters_"
+        );
+        assert_eq!(slugify("snake_case and ROS 2"), "snake_case-and-ros-2");
+        assert_eq!(slugify("Über Roboter"), "über-roboter");
+        assert_eq!(slugify("1 Hello, world!"), "1-hello-world");
+    }
+
+    #[test]
+    fn slugifies_like_pandoc() {
+        let style = SlugStyle::Pandoc;
+        assert_eq!(
+            style.slugify("Introducing Bradley + Luxonis"),
+            "introducing-bradley-luxonis"
+        );
+        assert_eq!(
+            style.slugify("ROS 2.0 and Über_Roboter"),
+            "ros-2.0-and-über_roboter"
+        );
+        assert_eq!(style.slugify("3. Wrap up"), "wrap-up");
+        assert_eq!(style.slugify("2024"), "section");
+    }
+
+    #[test]
+    fn slugifies_like_kramdown() {
+        let style = SlugStyle::Kramdown;
+        assert_eq!(
+            style.slugify("Introducing Bradley + Luxonis"),
+            "introducing-bradley--luxonis"
+        );
+        assert_eq!(style.slugify("Über snake_case"), "ber-snakecase");
+        assert_eq!(style.slugify("3. Wrap up"), "wrap-up");
+        assert_eq!(style.slugify("👍"), "section");
+    }
+
+    #[test]
+    fn numbers_repeated_slugs() {
+        let mut slugger = Slugger::new(SlugStyle::GitHub);
+
+        assert_eq!(slugger.slug("Questions"), "questions");
+        assert_eq!(slugger.slug("Questions"), "questions-1");
+        assert_eq!(slugger.slug("Questions 1"), "questions-1-1");
+        assert_eq!(slugger.slug("Questions"), "questions-2");
+    }
+
+    #[test]
+    fn parses_slug_styles() {
+        assert_eq!("GitHub".parse::<SlugStyle>(), Ok(SlugStyle::GitHub));
+        assert_eq!("jekyll".parse::<SlugStyle>(), Ok(SlugStyle::Kramdown));
+        assert!("asciidoc".parse::<SlugStyle>().is_err());
+        assert_eq!(SlugStyle::Pandoc.to_string(), "pandoc");
+    }
+}
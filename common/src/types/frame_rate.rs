@@ -0,0 +1,235 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::str::FromStr;
+
+/// Frame rates used by video editors when exporting SMPTE time codes.
+///
+/// Drop-frame (DF) rates skip frame labels at the start of most minutes so the
+/// labels stay close to wall-clock time. Non-drop-frame (NDF) fractional rates
+/// label every frame and drift behind wall-clock time by 3.6 seconds per hour.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub enum FrameRate {
+    Fps23_976,
+    Fps24,
+    Fps25,
+    Fps29_97Df,
+    Fps29_97Ndf,
+    Fps30,
+    Fps50,
+    Fps59_94Df,
+    Fps59_94Ndf,
+    Fps60,
+}
+
+impl FrameRate {
+    /// The number of frame labels per second, e.g. 30 for 29.97 fps.
+    pub fn timebase(&self) -> u32 {
+        match self {
+            FrameRate::Fps23_976 | FrameRate::Fps24 => 24,
+            FrameRate::Fps25 => 25,
+            FrameRate::Fps29_97Df | FrameRate::Fps29_97Ndf | FrameRate::Fps30 => 30,
+            FrameRate::Fps50 => 50,
+            FrameRate::Fps59_94Df | FrameRate::Fps59_94Ndf | FrameRate::Fps60 => 60,
+        }
+    }
+
+    /// The actual number of frames per second as a `(numerator, denominator)` pair.
+    pub fn fps(&self) -> (u64, u64) {
+        match self {
+            FrameRate::Fps23_976 => (24000, 1001),
+            FrameRate::Fps29_97Df | FrameRate::Fps29_97Ndf => (30000, 1001),
+            FrameRate::Fps59_94Df | FrameRate::Fps59_94Ndf => (60000, 1001),
+            _ => (self.timebase() as u64, 1),
+        }
+    }
+
+    pub fn is_drop_frame(&self) -> bool {
+        matches!(self, FrameRate::Fps29_97Df | FrameRate::Fps59_94Df)
+    }
+
+    /// Whether every frame label lines up exactly with wall-clock seconds.
+    pub fn is_integer(&self) -> bool {
+        self.fps().1 == 1
+    }
+
+    /// The drop-frame rate that `;`-separated time codes at this rate refer to.
+    pub fn as_drop_frame(&self) -> Option<FrameRate> {
+        match self.timebase() {
+            30 => Some(FrameRate::Fps29_97Df),
+            60 => Some(FrameRate::Fps59_94Df),
+            _ => None,
+        }
+    }
+
+    /// Labels dropped at the start of each minute that isn't a multiple of ten.
+    fn dropped_frames_per_minute(&self) -> u64 {
+        match self {
+            FrameRate::Fps29_97Df => 2,
+            FrameRate::Fps59_94Df => 4,
+            _ => 0,
+        }
+    }
+
+    /// Converts a SMPTE label into the number of frames since `00:00:00:00`.
+    pub fn frame_number(
+        &self,
+        hours: u32,
+        minutes: u32,
+        seconds: u32,
+        frames: u32,
+    ) -> Result<u64, Box<dyn Error>> {
+        if minutes > 59 {
+            return Err("Minutes must be between 0-59".into());
+        }
+        if seconds > 59 {
+            return Err("Seconds must be between 0-59".into());
+        }
+        let timebase = self.timebase() as u64;
+        if frames as u64 >= timebase {
+            return Err(
+                format!("Frames must be between 0-{} at {} fps", timebase - 1, self).into(),
+            );
+        }
+
+        let dropped = self.dropped_frames_per_minute();
+        if seconds == 0 && !minutes.is_multiple_of(10) && (frames as u64) < dropped {
+            return Err(format!(
+                "Frame {:02} does not exist at the start of minute {} in {} drop-frame time code",
+                frames, minutes, self
+            )
+            .into());
+        }
+
+        let total_minutes = hours as u64 * 60 + minutes as u64;
+        let labels = (total_minutes * 60 + seconds as u64) * timebase + frames as u64;
+        Ok(labels - dropped * (total_minutes - total_minutes / 10))
+    }
+
+    /// The wall-clock time of a frame in nanoseconds.
+    pub fn frame_to_nanos(&self, frame_number: u64) -> u64 {
+        let (numerator, denominator) = self.fps();
+        (frame_number as u128 * 1_000_000_000 * denominator as u128 / numerator as u128) as u64
+    }
+}
+
+impl FromStr for FrameRate {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<FrameRate, Self::Err> {
+        match text.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "23.976" | "23.98" => Ok(FrameRate::Fps23_976),
+            "24" => Ok(FrameRate::Fps24),
+            "25" => Ok(FrameRate::Fps25),
+            "29.97df" => Ok(FrameRate::Fps29_97Df),
+            "29.97" | "29.97ndf" => Ok(FrameRate::Fps29_97Ndf),
+            "30" => Ok(FrameRate::Fps30),
+            "50" => Ok(FrameRate::Fps50),
+            "59.94df" => Ok(FrameRate::Fps59_94Df),
+            "59.94" | "59.94ndf" => Ok(FrameRate::Fps59_94Ndf),
+            "60" => Ok(FrameRate::Fps60),
+            _ => Err(format!(
+                "Unsupported frame rate '{}', expected one of 23.976, 24, 25, 29.97df, 29.97ndf, 30, 50, 59.94df, 59.94ndf or 60",
+                text
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for FrameRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            FrameRate::Fps23_976 => "23.976",
+            FrameRate::Fps24 => "24",
+            FrameRate::Fps25 => "25",
+            FrameRate::Fps29_97Df => "29.97df",
+            FrameRate::Fps29_97Ndf => "29.97ndf",
+            FrameRate::Fps30 => "30",
+            FrameRate::Fps50 => "50",
+            FrameRate::Fps59_94Df => "59.94df",
+            FrameRate::Fps59_94Ndf => "59.94ndf",
+            FrameRate::Fps60 => "60",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod frame_number {
+        use super::*;
+
+        #[test]
+        fn counts_every_label_without_drop_frame() {
+            assert_eq!(FrameRate::Fps25.frame_number(0, 0, 1, 0).unwrap(), 25);
+            assert_eq!(
+                FrameRate::Fps29_97Ndf.frame_number(1, 0, 0, 0).unwrap(),
+                108000
+            );
+            assert_eq!(FrameRate::Fps23_976.frame_number(0, 1, 0, 1).unwrap(), 1441);
+        }
+
+        #[test]
+        fn skips_dropped_labels() {
+            let rate = FrameRate::Fps29_97Df;
+            assert_eq!(rate.frame_number(0, 0, 59, 29).unwrap(), 1799);
+            assert_eq!(rate.frame_number(0, 1, 0, 2).unwrap(), 1800);
+            assert_eq!(rate.frame_number(0, 10, 0, 0).unwrap(), 17982);
+            assert_eq!(rate.frame_number(1, 0, 0, 0).unwrap(), 107892);
+            assert_eq!(
+                FrameRate::Fps59_94Df.frame_number(0, 1, 0, 4).unwrap(),
+                3600
+            );
+        }
+
+        #[test]
+        fn rejects_labels_that_do_not_exist() {
+            assert!(FrameRate::Fps29_97Df.frame_number(0, 1, 0, 0).is_err());
+            assert!(FrameRate::Fps29_97Df.frame_number(0, 1, 0, 1).is_err());
+            assert!(FrameRate::Fps29_97Df.frame_number(0, 10, 0, 0).is_ok());
+            assert!(FrameRate::Fps25.frame_number(0, 0, 0, 25).is_err());
+            assert!(FrameRate::Fps30.frame_number(0, 60, 0, 0).is_err());
+        }
+    }
+
+    mod from_str {
+        use super::*;
+
+        #[test]
+        fn parses_supported_rates() {
+            assert_eq!("23.976".parse::<FrameRate>().unwrap(), FrameRate::Fps23_976);
+            assert_eq!(
+                "29.97".parse::<FrameRate>().unwrap(),
+                FrameRate::Fps29_97Ndf
+            );
+            assert_eq!(
+                "29.97 DF".parse::<FrameRate>().unwrap(),
+                FrameRate::Fps29_97Df
+            );
+            assert_eq!(
+                "59.94-df".parse::<FrameRate>().unwrap(),
+                FrameRate::Fps59_94Df
+            );
+            assert!("48".parse::<FrameRate>().is_err());
+        }
+
+        #[test]
+        fn round_trips_through_display() {
+            for rate in [
+                FrameRate::Fps23_976,
+                FrameRate::Fps24,
+                FrameRate::Fps25,
+                FrameRate::Fps29_97Df,
+                FrameRate::Fps29_97Ndf,
+                FrameRate::Fps30,
+                FrameRate::Fps50,
+                FrameRate::Fps59_94Df,
+                FrameRate::Fps59_94Ndf,
+                FrameRate::Fps60,
+            ] {
+                assert_eq!(rate.to_string().parse::<FrameRate>().unwrap(), rate);
+            }
+        }
+    }
+}
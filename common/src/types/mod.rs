@@ -1,5 +1,8 @@
+pub mod frame_rate;
+pub use crate::types::frame_rate::FrameRate;
+
 pub mod time_code;
-pub use crate::types::time_code::TimeCode;
+pub use crate::types::time_code::{Frames, TimeCode};
 
 pub mod outline_entry;
 pub use crate::types::outline_entry::OutlineEntry;
@@ -2,10 +2,28 @@ use crate::types::time_code::TimeCode;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
 pub struct OutlineEntry {
     pub time_code: TimeCode,
     pub text: String,
+    /// How deeply the entry is nested, where `0` is a top level chapter and
+    /// `1` is a sub-topic of the chapter before it.
+    #[serde(default)]
+    pub level: usize,
+    /// A web page about the chapter, shown by podcast apps.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// An image shown while the chapter plays.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub img: Option<String>,
+    /// Whether the chapter is left out of tables of contents. Hidden chapters
+    /// still mark where a topic starts, so players can show their titles and
+    /// images as they play.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+    /// Longer notes about the chapter, for templates to show or leave out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
 }
 
 impl Eq for OutlineEntry {}
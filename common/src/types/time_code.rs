@@ -1,15 +1,44 @@
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::error::Error;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::str::FromStr;
+use std::time::Duration;
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+use crate::types::frame_rate::FrameRate;
+
+/// A frame count within a wall-clock second, along with the integer frame rate
+/// it was counted at.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub struct Frames {
+    pub count: u32,
+    pub rate: u32,
+}
+
+/// A position in an episode.
+///
+/// The sub-second part is stored either as `milliseconds` or as `frames`. Whole
+/// second time codes leave both empty and display and serialize exactly as
+/// `H:MM:SS` / `M:SS` values.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
 pub struct TimeCode {
     pub hours: u32,
     pub minutes: u32,
     pub seconds: u32,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub milliseconds: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frames: Option<Frames>,
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
 }
 
 impl TimeCode {
+    /// Frame rate assumed for SMPTE `HH:MM:SS:FF` time codes when none is given.
+    pub const DEFAULT_FRAME_RATE: FrameRate = FrameRate::Fps30;
+
     pub fn new(hours: u32, minutes: u32, seconds: u32) -> Result<TimeCode, Box<dyn Error>> {
         if minutes > 59 {
             return Err("Minutes must be between 0-59".into());
@@ -21,53 +50,372 @@ impl TimeCode {
             hours,
             minutes,
             seconds,
+            ..Default::default()
         })
     }
-}
 
-impl PartialOrd for TimeCode {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.hours > other.hours {
-            Some(Ordering::Greater)
-        } else if self.hours < other.hours {
-            Some(Ordering::Less)
-        } else if self.minutes > other.minutes {
-            Some(Ordering::Greater)
-        } else if self.minutes < other.minutes {
-            Some(Ordering::Less)
-        } else if self.seconds > other.seconds {
-            Some(Ordering::Greater)
-        } else if self.seconds < other.seconds {
-            Some(Ordering::Less)
-        } else {
-            Some(Ordering::Equal)
+    pub fn with_milliseconds(
+        hours: u32,
+        minutes: u32,
+        seconds: u32,
+        milliseconds: u32,
+    ) -> Result<TimeCode, Box<dyn Error>> {
+        if milliseconds > 999 {
+            return Err("Milliseconds must be between 0-999".into());
         }
+        Ok(TimeCode {
+            milliseconds,
+            ..TimeCode::new(hours, minutes, seconds)?
+        })
     }
-}
 
-impl TimeCode {
-    pub fn from_str(text: &str) -> Result<TimeCode, Box<dyn Error>> {
-        let groups = text
-            .split(":")
+    pub fn with_frames(
+        hours: u32,
+        minutes: u32,
+        seconds: u32,
+        frames: u32,
+        frame_rate: u32,
+    ) -> Result<TimeCode, Box<dyn Error>> {
+        if frame_rate == 0 {
+            return Err("Frame rate must be greater than 0".into());
+        }
+        if frames >= frame_rate {
+            return Err(format!("Frames must be between 0-{}", frame_rate - 1).into());
+        }
+        Ok(TimeCode {
+            frames: Some(Frames {
+                count: frames,
+                rate: frame_rate,
+            }),
+            ..TimeCode::new(hours, minutes, seconds)?
+        })
+    }
+
+    /// Parses a time code, reading SMPTE `HH:MM:SS:FF` values at `frame_rate`.
+    ///
+    /// A `;` before the frames, as in `01:00:00;02`, marks a drop-frame time code
+    /// and selects the drop-frame variant of `frame_rate`. A rate after an `@`,
+    /// as in `0:01:02:03@25`, is used instead of `frame_rate`.
+    pub fn from_str_with_frame_rate(
+        text: &str,
+        frame_rate: FrameRate,
+    ) -> Result<TimeCode, Box<dyn Error>> {
+        if let Some((label, rate)) = text.split_once('@') {
+            let groups = label
+                .split(':')
+                .map(|s| s.parse::<u32>())
+                .collect::<Result<Vec<u32>, _>>()?;
+            let [hours, minutes, seconds, frames] = groups[..] else {
+                return Err(format!("Invalid SMPTE time code: {}", text).into());
+            };
+            return TimeCode::with_frames(hours, minutes, seconds, frames, rate.parse::<u32>()?);
+        }
+        if text.split([':', ';']).count() == 4 {
+            return TimeCode::from_smpte(text, frame_rate);
+        }
+
+        let groups = text.split(':').collect::<Vec<&str>>();
+        let (last, rest) = groups.split_last().ok_or("Time code is empty")?;
+        let (seconds, milliseconds) = match last.split_once(['.', ',']) {
+            Some((seconds, fraction)) => (seconds, parse_milliseconds(fraction)?),
+            None => (*last, 0),
+        };
+        let seconds = seconds.parse::<u32>()?;
+        let rest = rest
+            .iter()
             .map(|s| s.parse::<u32>())
             .collect::<Result<Vec<u32>, _>>()?;
-        if groups.len() == 3 {
-            return TimeCode::new(groups[0], groups[1], groups[2]);
-        } else if groups.len() == 2 {
-            return TimeCode::new(0, groups[0], groups[1]);
+        match rest.as_slice() {
+            [hours, minutes] => {
+                TimeCode::with_milliseconds(*hours, *minutes, seconds, milliseconds)
+            }
+            [minutes] => TimeCode::with_milliseconds(0, *minutes, seconds, milliseconds),
+            _ => Err(format!("Invalid time code: {}", text).into()),
+        }
+    }
+
+    /// Parses a SMPTE `HH:MM:SS:FF` or drop-frame `HH:MM:SS;FF` label and
+    /// normalizes it to wall-clock time.
+    ///
+    /// Labels at integer frame rates keep their frame count. Labels at
+    /// fractional rates are converted to the nearest millisecond.
+    pub fn from_smpte(text: &str, frame_rate: FrameRate) -> Result<TimeCode, Box<dyn Error>> {
+        let frame_rate = if text.contains(';') {
+            frame_rate.as_drop_frame().ok_or(format!(
+                "Drop-frame time code {} is not defined at {} fps",
+                text, frame_rate
+            ))?
         } else {
-            return Err(format!("Invalid time code: {}", text).into());
+            frame_rate
+        };
+        let groups = text
+            .split([':', ';'])
+            .map(|s| s.parse::<u32>())
+            .collect::<Result<Vec<u32>, _>>()?;
+        let [hours, minutes, seconds, frames] = groups[..] else {
+            return Err(format!("Invalid SMPTE time code: {}", text).into());
+        };
+        let frame_number = frame_rate.frame_number(hours, minutes, seconds, frames)?;
+        Ok(TimeCode::from_frame_number(frame_number, frame_rate))
+    }
+
+    /// The wall-clock time of a frame counted from the start of the timeline.
+    pub fn from_frame_number(frame_number: u64, frame_rate: FrameRate) -> TimeCode {
+        if frame_rate.is_integer() {
+            let timebase = frame_rate.timebase() as u64;
+            let mut time_code = TimeCode::from_total_milliseconds(frame_number / timebase * 1000);
+            time_code.frames = Some(Frames {
+                count: (frame_number % timebase) as u32,
+                rate: timebase as u32,
+            });
+            return time_code;
+        }
+        let nanos = frame_rate.frame_to_nanos(frame_number);
+        TimeCode::from_total_milliseconds((nanos + 500_000) / 1_000_000)
+    }
+
+    fn from_total_milliseconds(total_milliseconds: u64) -> TimeCode {
+        let total_seconds = total_milliseconds / 1000;
+        TimeCode {
+            hours: (total_seconds / 3600) as u32,
+            minutes: (total_seconds / 60 % 60) as u32,
+            seconds: (total_seconds % 60) as u32,
+            milliseconds: (total_milliseconds % 1000) as u32,
+            frames: None,
+        }
+    }
+
+    /// The sub-second part of the time code in nanoseconds.
+    pub fn subsec_nanos(&self) -> u64 {
+        let from_frames = self.frames.map_or(0, |frames| {
+            (frames.count as u64 * 1_000_000_000)
+                .checked_div(frames.rate as u64)
+                .unwrap_or(0)
+        });
+        self.milliseconds as u64 * 1_000_000 + from_frames
+    }
+
+    /// Builds a time code from components that may overflow, carrying extra
+    /// seconds into minutes and extra minutes into hours.
+    pub fn normalized(hours: u32, minutes: u32, seconds: u32) -> TimeCode {
+        let total_seconds = hours as u64 * 3600 + minutes as u64 * 60 + seconds as u64;
+        TimeCode::from_total_milliseconds(total_seconds * 1000)
+    }
+
+    /// The time since the start of the episode.
+    pub fn to_duration(&self) -> Duration {
+        let seconds = self.hours as u64 * 3600 + self.minutes as u64 * 60 + self.seconds as u64;
+        Duration::from_secs(seconds) + Duration::from_nanos(self.subsec_nanos())
+    }
+
+    /// Converts a duration into a time code with millisecond precision.
+    ///
+    /// Returns `None` if the hours don't fit in the time code.
+    pub fn from_duration(duration: Duration) -> Option<TimeCode> {
+        if duration.as_secs() / 3600 > u32::MAX as u64 {
+            return None;
         }
+        Some(TimeCode::from_total_milliseconds(
+            duration.as_millis() as u64
+        ))
+    }
+
+    pub fn to_seconds(&self) -> f64 {
+        self.to_duration().as_secs_f64()
+    }
+
+    /// Converts seconds into a time code, rounding to the nearest millisecond.
+    pub fn from_seconds(seconds: f64) -> Result<TimeCode, Box<dyn Error>> {
+        if !seconds.is_finite() || seconds < 0.0 {
+            return Err(format!("Seconds must be a positive number: {}", seconds).into());
+        }
+        let milliseconds = (seconds * 1000.0).round();
+        TimeCode::from_duration(Duration::from_millis(milliseconds as u64))
+            .ok_or_else(|| format!("Seconds are too large for a time code: {}", seconds).into())
+    }
+
+    pub fn checked_add(&self, duration: Duration) -> Option<TimeCode> {
+        let sum = self.to_duration().checked_add(duration)?;
+        self.with_same_precision(sum)
+    }
+
+    pub fn checked_sub(&self, duration: Duration) -> Option<TimeCode> {
+        let difference = self.to_duration().checked_sub(duration)?;
+        self.with_same_precision(difference)
+    }
+
+    /// Adds a duration, clamping at the largest representable time code.
+    pub fn saturating_add(&self, duration: Duration) -> TimeCode {
+        self.checked_add(duration).unwrap_or(TimeCode {
+            hours: u32::MAX,
+            minutes: 59,
+            seconds: 59,
+            milliseconds: 999,
+            frames: None,
+        })
+    }
+
+    /// Subtracts a duration, clamping at `0:00`.
+    pub fn saturating_sub(&self, duration: Duration) -> TimeCode {
+        self.checked_sub(duration).unwrap_or_default()
+    }
+
+    /// The time from `earlier` to this time code, or `None` if `earlier` is later.
+    pub fn checked_duration_since(&self, earlier: &TimeCode) -> Option<Duration> {
+        self.to_duration().checked_sub(earlier.to_duration())
+    }
+
+    /// The time from `earlier` to this time code, or zero if `earlier` is later.
+    pub fn saturating_duration_since(&self, earlier: &TimeCode) -> Duration {
+        self.checked_duration_since(earlier).unwrap_or_default()
+    }
+
+    /// Converts a duration into a time code counted in the same frames as this one,
+    /// or in milliseconds if this time code has no frames.
+    fn with_same_precision(&self, duration: Duration) -> Option<TimeCode> {
+        let Some(frames) = self.frames else {
+            return TimeCode::from_duration(duration);
+        };
+        let rate = frames.rate as u128;
+        let total_frames = (duration.as_nanos() * rate + 500_000_000) / 1_000_000_000;
+        let mut time_code =
+            TimeCode::from_duration(Duration::from_secs((total_frames / rate) as u64))?;
+        time_code.frames = Some(Frames {
+            count: (total_frames % rate) as u32,
+            rate: frames.rate,
+        });
+        Some(time_code)
     }
 }
 
+impl Add<Duration> for TimeCode {
+    type Output = TimeCode;
+
+    /// Adds a duration, keeping the time code's frames or milliseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result doesn't fit in a time code. Use `checked_add` or
+    /// `saturating_add` to handle that instead.
+    fn add(self, duration: Duration) -> TimeCode {
+        self.checked_add(duration)
+            .expect("overflow when adding duration to time code")
+    }
+}
+
+impl AddAssign<Duration> for TimeCode {
+    /// # Panics
+    ///
+    /// Panics if the result doesn't fit in a time code, like `+`.
+    fn add_assign(&mut self, duration: Duration) {
+        *self = *self + duration;
+    }
+}
+
+impl Sub<Duration> for TimeCode {
+    type Output = TimeCode;
+
+    /// Subtracts a duration, keeping the time code's frames or milliseconds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the duration is longer than the time code. Use `checked_sub`
+    /// or `saturating_sub` to handle that instead.
+    fn sub(self, duration: Duration) -> TimeCode {
+        self.checked_sub(duration)
+            .expect("overflow when subtracting duration from time code")
+    }
+}
+
+impl SubAssign<Duration> for TimeCode {
+    /// # Panics
+    ///
+    /// Panics if the duration is longer than the time code, like `-`.
+    fn sub_assign(&mut self, duration: Duration) {
+        *self = *self - duration;
+    }
+}
+
+impl Sub<TimeCode> for TimeCode {
+    type Output = Duration;
+
+    /// The time from `earlier` to this time code.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `earlier` is later than this time code. Use
+    /// `checked_duration_since` or `saturating_duration_since` to handle that
+    /// instead.
+    fn sub(self, earlier: TimeCode) -> Duration {
+        self.checked_duration_since(&earlier)
+            .expect("subtracted time code is later than this one")
+    }
+}
+
+fn parse_milliseconds(fraction: &str) -> Result<u32, Box<dyn Error>> {
+    if fraction.is_empty() || fraction.len() > 3 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid fraction of a second: {}", fraction).into());
+    }
+    Ok(format!("{:0<3}", fraction).parse::<u32>()?)
+}
+
+impl PartialEq for TimeCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TimeCode {}
+
+impl Ord for TimeCode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.hours
+            .cmp(&other.hours)
+            .then(self.minutes.cmp(&other.minutes))
+            .then(self.seconds.cmp(&other.seconds))
+            .then(self.subsec_nanos().cmp(&other.subsec_nanos()))
+    }
+}
+
+impl PartialOrd for TimeCode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for TimeCode {
+    type Err = Box<dyn Error>;
+
+    fn from_str(text: &str) -> Result<TimeCode, Self::Err> {
+        TimeCode::from_str_with_frame_rate(text, TimeCode::DEFAULT_FRAME_RATE)
+    }
+}
+
+/// Frames are written as `H:MM:SS:FF`, followed by their rate, as in
+/// `0:01:02:03@25`, unless it is the default 30 fps, so that parsing the
+/// text gives back the same time code.
 impl std::fmt::Display for TimeCode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(frames) = self.frames {
+            write!(
+                f,
+                "{}:{:02}:{:02}:{:02}",
+                self.hours, self.minutes, self.seconds, frames.count
+            )?;
+            if frames.rate != TimeCode::DEFAULT_FRAME_RATE.timebase() {
+                write!(f, "@{}", frames.rate)?;
+            }
+            return Ok(());
+        }
         if self.hours > 0 {
-            write!(f, "{}:{:02}:{:02}", self.hours, self.minutes, self.seconds)
+            write!(f, "{}:{:02}:{:02}", self.hours, self.minutes, self.seconds)?;
         } else {
-            write!(f, "{}:{:02}", self.minutes, self.seconds)
+            write!(f, "{}:{:02}", self.minutes, self.seconds)?;
+        }
+        if self.milliseconds > 0 {
+            write!(f, ".{:03}", self.milliseconds)?;
         }
+        Ok(())
     }
 }
 
@@ -85,7 +433,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 0,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -93,7 +442,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 0,
-                    seconds: 1
+                    seconds: 1,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -101,7 +451,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 1,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -109,7 +460,8 @@ mod test {
                 TimeCode {
                     hours: 1,
                     minutes: 0,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -117,7 +469,8 @@ mod test {
                 TimeCode {
                     hours: 1,
                     minutes: 1,
-                    seconds: 1
+                    seconds: 1,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -126,6 +479,7 @@ mod test {
                     hours: 1,
                     minutes: 59,
                     seconds: 59,
+                    ..Default::default()
                 }
             );
         }
@@ -150,7 +504,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 0,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -158,7 +513,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 0,
-                    seconds: 1
+                    seconds: 1,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -166,7 +522,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 1,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -174,7 +531,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 1,
-                    seconds: 1
+                    seconds: 1,
+                    ..Default::default()
                 }
             );
         }
@@ -186,7 +544,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 0,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -194,7 +553,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 0,
-                    seconds: 1
+                    seconds: 1,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -202,7 +562,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 1,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -210,7 +571,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 1,
-                    seconds: 1
+                    seconds: 1,
+                    ..Default::default()
                 }
             );
         }
@@ -222,7 +584,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 0,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -230,7 +593,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 0,
-                    seconds: 1
+                    seconds: 1,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -238,7 +602,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 1,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -246,7 +611,8 @@ mod test {
                 TimeCode {
                     hours: 1,
                     minutes: 0,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -254,7 +620,8 @@ mod test {
                 TimeCode {
                     hours: 1,
                     minutes: 1,
-                    seconds: 1
+                    seconds: 1,
+                    ..Default::default()
                 }
             );
         }
@@ -266,7 +633,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 0,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -274,7 +642,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 0,
-                    seconds: 1
+                    seconds: 1,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -282,7 +651,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 1,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -290,7 +660,8 @@ mod test {
                 TimeCode {
                     hours: 1,
                     minutes: 0,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
             );
             assert_eq!(
@@ -298,7 +669,8 @@ mod test {
                 TimeCode {
                     hours: 1,
                     minutes: 1,
-                    seconds: 1
+                    seconds: 1,
+                    ..Default::default()
                 }
             );
         }
@@ -310,11 +682,113 @@ mod test {
             assert!(TimeCode::from_str(":00:00").is_err());
             assert!(TimeCode::from_str("::").is_err());
             assert!(TimeCode::from_str("a:b:c").is_err());
-            assert!(TimeCode::from_str("00:00:00:00").is_err());
+            assert!(TimeCode::from_str("00:00:00:30").is_err());
+            assert!(TimeCode::from_str("00:00:01.").is_err());
+            assert!(TimeCode::from_str("00:00:01.1234").is_err());
+            assert!(TimeCode::from_str("00:00:01.a").is_err());
             assert!(TimeCode::from_str("00:00:00:00:00").is_err());
         }
     }
 
+    mod from_string_with_precision {
+
+        use super::*;
+
+        #[test]
+        fn with_milliseconds() {
+            assert_eq!(
+                TimeCode::from_str("00:01:02.500").unwrap(),
+                TimeCode::with_milliseconds(0, 1, 2, 500).unwrap()
+            );
+            assert_eq!(
+                TimeCode::from_str("1:02:03.04").unwrap(),
+                TimeCode::with_milliseconds(1, 2, 3, 40).unwrap()
+            );
+            assert_eq!(
+                TimeCode::from_str("1:02.5").unwrap(),
+                TimeCode::with_milliseconds(0, 1, 2, 500).unwrap()
+            );
+        }
+
+        #[test]
+        fn with_comma_separated_milliseconds() {
+            assert_eq!(
+                TimeCode::from_str("00:01:02,250").unwrap(),
+                TimeCode::with_milliseconds(0, 1, 2, 250).unwrap()
+            );
+        }
+
+        #[test]
+        fn with_smpte_frames() {
+            assert_eq!(
+                TimeCode::from_str("01:02:03:15").unwrap(),
+                TimeCode {
+                    hours: 1,
+                    minutes: 2,
+                    seconds: 3,
+                    frames: Some(Frames {
+                        count: 15,
+                        rate: 30
+                    }),
+                    ..Default::default()
+                }
+            );
+            assert_eq!(
+                TimeCode::from_str_with_frame_rate("00:00:01:24", FrameRate::Fps25).unwrap(),
+                TimeCode::with_frames(0, 0, 1, 24, 25).unwrap()
+            );
+            assert!(TimeCode::from_str_with_frame_rate("00:00:01:25", FrameRate::Fps25).is_err());
+        }
+
+        #[test]
+        fn with_drop_frame() {
+            assert_eq!(
+                TimeCode::from_str("01:00:00;02").unwrap(),
+                TimeCode::with_milliseconds(1, 0, 0, 63).unwrap()
+            );
+            assert_eq!(
+                TimeCode::from_str_with_frame_rate("00:10:00;00", FrameRate::Fps29_97Df).unwrap(),
+                TimeCode::with_milliseconds(0, 9, 59, 999).unwrap()
+            );
+            assert!(TimeCode::from_str("00:01:00;00").is_err());
+            assert!(TimeCode::from_str_with_frame_rate("00:00:01;00", FrameRate::Fps25).is_err());
+        }
+
+        #[test]
+        fn normalizes_fractional_rates_to_wall_clock() {
+            assert_eq!(
+                TimeCode::from_smpte("01:00:00:00", FrameRate::Fps29_97Ndf).unwrap(),
+                TimeCode::with_milliseconds(1, 0, 3, 600).unwrap()
+            );
+            assert_eq!(
+                TimeCode::from_smpte("00:00:01:00", FrameRate::Fps23_976).unwrap(),
+                TimeCode::with_milliseconds(0, 0, 1, 1).unwrap()
+            );
+            assert_eq!(
+                TimeCode::from_smpte("01:00:00:30", FrameRate::Fps60).unwrap(),
+                TimeCode::with_frames(1, 0, 0, 30, 60).unwrap()
+            );
+        }
+    }
+
+    mod ordering {
+
+        use super::*;
+
+        #[test]
+        fn compares_sub_second_precision() {
+            let whole = TimeCode::new(0, 1, 2).unwrap();
+            let half = TimeCode::with_milliseconds(0, 1, 2, 500).unwrap();
+            let frames = TimeCode::with_frames(0, 1, 2, 12, 24).unwrap();
+            let next = TimeCode::new(0, 1, 3).unwrap();
+
+            assert!(whole < half);
+            assert!(half < next);
+            assert_eq!(half, frames);
+            assert_eq!(half.cmp(&frames), Ordering::Equal);
+        }
+    }
+
     mod display {
 
         use super::*;
@@ -325,7 +799,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 0,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
                 .to_string(),
                 "0:00"
@@ -334,7 +809,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 0,
-                    seconds: 1
+                    seconds: 1,
+                    ..Default::default()
                 }
                 .to_string(),
                 "0:01"
@@ -343,7 +819,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 1,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
                 .to_string(),
                 "1:00"
@@ -352,7 +829,8 @@ mod test {
                 TimeCode {
                     hours: 0,
                     minutes: 10,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
                 .to_string(),
                 "10:00"
@@ -361,7 +839,8 @@ mod test {
                 TimeCode {
                     hours: 1,
                     minutes: 0,
-                    seconds: 0
+                    seconds: 0,
+                    ..Default::default()
                 }
                 .to_string(),
                 "1:00:00"
@@ -370,7 +849,8 @@ mod test {
                 TimeCode {
                     hours: 1,
                     minutes: 1,
-                    seconds: 1
+                    seconds: 1,
+                    ..Default::default()
                 }
                 .to_string(),
                 "1:01:01"
@@ -379,7 +859,8 @@ mod test {
                 TimeCode {
                     hours: 10,
                     minutes: 10,
-                    seconds: 10
+                    seconds: 10,
+                    ..Default::default()
                 }
                 .to_string(),
                 "10:10:10"
@@ -388,11 +869,146 @@ mod test {
                 TimeCode {
                     hours: 100,
                     minutes: 59,
-                    seconds: 59
+                    seconds: 59,
+                    ..Default::default()
                 }
                 .to_string(),
                 "100:59:59"
             )
         }
+
+        #[test]
+        fn displays_sub_second_precision() {
+            assert_eq!(
+                TimeCode::with_milliseconds(0, 1, 2, 5).unwrap().to_string(),
+                "1:02.005"
+            );
+            assert_eq!(
+                TimeCode::with_milliseconds(1, 2, 3, 450)
+                    .unwrap()
+                    .to_string(),
+                "1:02:03.450"
+            );
+            assert_eq!(
+                TimeCode::with_frames(0, 1, 2, 3, 30).unwrap().to_string(),
+                "0:01:02:03"
+            );
+            assert_eq!(
+                TimeCode::with_frames(0, 1, 2, 3, 25).unwrap().to_string(),
+                "0:01:02:03@25"
+            );
+        }
+
+        #[test]
+        fn round_trips_through_from_str() {
+            for text in [
+                "1:02",
+                "1:02:03",
+                "1:02.005",
+                "1:02:03.450",
+                "0:01:02:03",
+                "0:01:02:23@24",
+                "1:00:00:49@50",
+            ] {
+                assert_eq!(TimeCode::from_str(text).unwrap().to_string(), text);
+            }
+            for time_code in [
+                TimeCode::with_frames(0, 1, 2, 3, 25).unwrap(),
+                TimeCode::from_smpte("01:00:00:30", FrameRate::Fps60).unwrap(),
+            ] {
+                let parsed = TimeCode::from_str(&time_code.to_string()).unwrap();
+                assert_eq!(parsed, time_code);
+                assert_eq!(parsed.frames, time_code.frames);
+            }
+            assert!(TimeCode::from_str("0:01:02:25@25").is_err());
+            assert!(TimeCode::from_str("0:01:02@25").is_err());
+        }
+    }
+
+    mod arithmetic {
+
+        use super::*;
+
+        #[test]
+        fn adds_and_subtracts_durations() {
+            let time_code = TimeCode::new(0, 59, 30).unwrap();
+            assert_eq!(
+                time_code + Duration::from_secs(45),
+                TimeCode::new(1, 0, 15).unwrap()
+            );
+            assert_eq!(
+                time_code - Duration::from_secs(90),
+                TimeCode::new(0, 58, 0).unwrap()
+            );
+            assert_eq!(
+                time_code + Duration::from_millis(250),
+                TimeCode::with_milliseconds(0, 59, 30, 250).unwrap()
+            );
+
+            let mut time_code = time_code;
+            time_code += Duration::from_secs(30);
+            time_code -= Duration::from_secs(60);
+            assert_eq!(time_code, TimeCode::new(0, 59, 0).unwrap());
+        }
+
+        #[test]
+        fn keeps_frame_precision() {
+            let time_code = TimeCode::with_frames(0, 0, 1, 20, 25).unwrap();
+            assert_eq!(
+                time_code + Duration::from_millis(200),
+                TimeCode::with_frames(0, 0, 2, 0, 25).unwrap()
+            );
+            assert_eq!(
+                (time_code + Duration::from_millis(200))
+                    .frames
+                    .unwrap()
+                    .rate,
+                25
+            );
+        }
+
+        #[test]
+        fn checked_and_saturating() {
+            let time_code = TimeCode::new(0, 0, 10).unwrap();
+            assert_eq!(time_code.checked_sub(Duration::from_secs(11)), None);
+            assert_eq!(
+                time_code.saturating_sub(Duration::from_secs(11)),
+                TimeCode::new(0, 0, 0).unwrap()
+            );
+            assert_eq!(
+                time_code.checked_add(Duration::from_secs(5)),
+                Some(TimeCode::new(0, 0, 15).unwrap())
+            );
+            assert_eq!(time_code.checked_add(Duration::MAX), None);
+            assert_eq!(time_code.saturating_add(Duration::MAX).hours, u32::MAX);
+        }
+
+        #[test]
+        fn differences_between_time_codes() {
+            let start = TimeCode::new(0, 12, 30).unwrap();
+            let end = TimeCode::with_milliseconds(1, 0, 0, 500).unwrap();
+            assert_eq!(end - start, Duration::from_millis(2850500));
+            assert_eq!(start.checked_duration_since(&end), None);
+            assert_eq!(start.saturating_duration_since(&end), Duration::ZERO);
+        }
+
+        #[test]
+        fn converts_seconds() {
+            assert_eq!(
+                TimeCode::from_seconds(3725.5).unwrap(),
+                TimeCode::with_milliseconds(1, 2, 5, 500).unwrap()
+            );
+            assert_eq!(TimeCode::new(1, 2, 5).unwrap().to_seconds(), 3725.0);
+            assert!(TimeCode::from_seconds(-1.0).is_err());
+            assert!(TimeCode::from_seconds(f64::NAN).is_err());
+        }
+
+        #[test]
+        fn normalizes_overflow() {
+            assert_eq!(
+                TimeCode::normalized(0, 90, 75),
+                TimeCode::new(1, 31, 15).unwrap()
+            );
+        }
     }
 }
@@ -0,0 +1,361 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::ErrorKind;
+use crate::types::{OutlineEntry, TimeCode};
+
+/// Requirements a platform places on chapters before it will show them.
+///
+/// The count, first chapter and length rules apply to top level chapters,
+/// since nested and hidden entries aren't listed as chapters. Ordering and duplicate
+/// rules apply to every entry.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChapterRules {
+    pub name: String,
+    pub first_chapter_at_zero: bool,
+    pub min_chapters: usize,
+    pub min_chapter_length: Duration,
+}
+
+impl ChapterRules {
+    /// YouTube only shows chapters if the first starts at 0:00, there are at
+    /// least three, and each lasts at least 10 seconds.
+    pub fn youtube() -> ChapterRules {
+        ChapterRules {
+            name: "YouTube".to_string(),
+            first_chapter_at_zero: true,
+            min_chapters: 3,
+            min_chapter_length: Duration::from_secs(10),
+        }
+    }
+
+    /// Spotify reads chapters from time codes in the episode description. Like
+    /// YouTube it needs the first one to start at 0:00. Spotify doesn't
+    /// publish a minimum count or length, so two chapters of 5 seconds are
+    /// only defaults, which can be changed by building `ChapterRules` directly.
+    pub fn spotify() -> ChapterRules {
+        ChapterRules {
+            name: "Spotify".to_string(),
+            first_chapter_at_zero: true,
+            min_chapters: 2,
+            min_chapter_length: Duration::from_secs(5),
+        }
+    }
+
+    /// Podcasting 2.0 chapters files only need ordered chapters with titles.
+    pub fn podcasting_2_0() -> ChapterRules {
+        ChapterRules {
+            name: "Podcasting 2.0".to_string(),
+            first_chapter_at_zero: false,
+            min_chapters: 1,
+            min_chapter_length: Duration::ZERO,
+        }
+    }
+}
+
+impl FromStr for ChapterRules {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<ChapterRules, Self::Err> {
+        match text
+            .to_lowercase()
+            .replace(['-', '_', ' ', '.'], "")
+            .as_str()
+        {
+            "youtube" => Ok(ChapterRules::youtube()),
+            "spotify" => Ok(ChapterRules::spotify()),
+            "podcasting20" | "podcasting2" => Ok(ChapterRules::podcasting_2_0()),
+            _ => Err(format!(
+                "Unknown chapter rules '{}', expected youtube, spotify or podcasting2.0",
+                text
+            )),
+        }
+    }
+}
+
+/// A way an outline breaks a platform's chapter rules.
+#[derive(Debug, PartialEq, Clone)]
+pub enum OutlineViolation {
+    FirstChapterNotAtZero {
+        entry: OutlineEntry,
+    },
+    TooFewChapters {
+        count: usize,
+        min_chapters: usize,
+    },
+    ChapterTooShort {
+        entry: OutlineEntry,
+        length: Duration,
+        min_length: Duration,
+    },
+    OutOfOrder {
+        entry: OutlineEntry,
+        previous: OutlineEntry,
+    },
+    DuplicateTimeCode {
+        entry: OutlineEntry,
+        previous: OutlineEntry,
+    },
+}
+
+impl std::fmt::Display for OutlineViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutlineViolation::FirstChapterNotAtZero { entry } => write!(
+                f,
+                "The first chapter '{}' starts at {} instead of 0:00",
+                entry.text, entry.time_code
+            ),
+            OutlineViolation::TooFewChapters {
+                count,
+                min_chapters,
+            } => write!(
+                f,
+                "There are {} chapters but at least {} are needed",
+                count, min_chapters
+            ),
+            OutlineViolation::ChapterTooShort {
+                entry,
+                length,
+                min_length,
+            } => write!(
+                f,
+                "Chapter '{}' at {} lasts {} seconds but must last at least {} seconds",
+                entry.text,
+                entry.time_code,
+                length.as_secs_f64(),
+                min_length.as_secs_f64()
+            ),
+            OutlineViolation::OutOfOrder { entry, previous } => write!(
+                f,
+                "'{}' at {} comes after '{}' at {}",
+                entry.text, entry.time_code, previous.text, previous.time_code
+            ),
+            OutlineViolation::DuplicateTimeCode { entry, previous } => write!(
+                f,
+                "'{}' and '{}' both start at {}",
+                previous.text, entry.text, entry.time_code
+            ),
+        }
+    }
+}
+
+/// Every way an outline breaks a platform's chapter rules.
+#[derive(Debug, PartialEq, Clone)]
+pub struct OutlineViolations {
+    pub rules: String,
+    pub violations: Vec<OutlineViolation>,
+}
+
+impl std::fmt::Display for OutlineViolations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The outline breaks {} chapter rules:", self.rules)?;
+        for violation in &self.violations {
+            write!(f, "\n- {}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for OutlineViolations {}
+
+impl OutlineViolations {
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::Chapters
+    }
+}
+
+/// Checks an outline, in the order it was written, against `rules`.
+///
+/// The last chapter's length is only checked when `episode_duration` is given.
+pub fn validate_outline(
+    entries: &[OutlineEntry],
+    rules: &ChapterRules,
+    episode_duration: Option<Duration>,
+) -> Vec<OutlineViolation> {
+    let mut violations = vec![];
+
+    for pair in entries.windows(2) {
+        let (previous, entry) = (&pair[0], &pair[1]);
+        if entry.time_code < previous.time_code {
+            violations.push(OutlineViolation::OutOfOrder {
+                entry: entry.clone(),
+                previous: previous.clone(),
+            });
+        } else if entry.time_code == previous.time_code {
+            violations.push(OutlineViolation::DuplicateTimeCode {
+                entry: entry.clone(),
+                previous: previous.clone(),
+            });
+        }
+    }
+
+    let mut chapters = entries
+        .iter()
+        .filter(|entry| entry.level == 0 && !entry.hidden)
+        .collect::<Vec<&OutlineEntry>>();
+    chapters.sort();
+
+    if chapters.len() < rules.min_chapters {
+        violations.push(OutlineViolation::TooFewChapters {
+            count: chapters.len(),
+            min_chapters: rules.min_chapters,
+        });
+    }
+    if let Some(first) = chapters.first() {
+        if rules.first_chapter_at_zero && first.time_code != TimeCode::default() {
+            violations.push(OutlineViolation::FirstChapterNotAtZero {
+                entry: (*first).clone(),
+            });
+        }
+    }
+    let episode_end = episode_duration.and_then(TimeCode::from_duration);
+    for (index, chapter) in chapters.iter().enumerate() {
+        let Some(end) = chapters
+            .get(index + 1)
+            .map(|next| next.time_code)
+            .or(episode_end)
+        else {
+            continue;
+        };
+        let length = end.saturating_duration_since(&chapter.time_code);
+        if length < rules.min_chapter_length && length > Duration::ZERO {
+            violations.push(OutlineViolation::ChapterTooShort {
+                entry: (*chapter).clone(),
+                length,
+                min_length: rules.min_chapter_length,
+            });
+        }
+    }
+
+    violations
+}
+
+/// Validates an outline, returning the violations as an error when `strict`
+/// is set and as warnings otherwise.
+pub fn check_outline(
+    entries: &[OutlineEntry],
+    rules: &ChapterRules,
+    episode_duration: Option<Duration>,
+    strict: bool,
+) -> Result<Vec<OutlineViolation>, OutlineViolations> {
+    let violations = validate_outline(entries, rules, episode_duration);
+    if strict && !violations.is_empty() {
+        return Err(OutlineViolations {
+            rules: rules.name.clone(),
+            violations,
+        });
+    }
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_outline;
+
+    #[test]
+    fn accepts_valid_youtube_outline() {
+        let entries = parse_outline(
+            "0:00 Start\n0:10 Intro\n  0:15 Background\n0:55 Sponsor | hidden\n1:00 Demo",
+        )
+        .expect("parse_outline should succeed");
+
+        assert_eq!(
+            validate_outline(&entries, &ChapterRules::youtube(), None),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn reports_every_youtube_violation() {
+        let entries = parse_outline("0:05 Start\n0:10 Intro\n0:10 Also intro\n0:08 Demo")
+            .expect("parse_outline should succeed");
+
+        let violations = validate_outline(&entries, &ChapterRules::youtube(), None);
+
+        assert_eq!(
+            violations
+                .iter()
+                .map(|violation| violation.to_string())
+                .collect::<Vec<String>>(),
+            vec![
+                "'Intro' and 'Also intro' both start at 0:10",
+                "'Demo' at 0:08 comes after 'Also intro' at 0:10",
+                "The first chapter 'Start' starts at 0:05 instead of 0:00",
+                "Chapter 'Start' at 0:05 lasts 3 seconds but must last at least 10 seconds",
+                "Chapter 'Demo' at 0:08 lasts 2 seconds but must last at least 10 seconds",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_the_last_chapter_against_the_episode_length() {
+        let entries = parse_outline("0:00 Start\n0:10 Intro\n0:20 Demo\n1:00 Thanks")
+            .expect("parse_outline should succeed");
+        let rules = ChapterRules::youtube();
+
+        assert_eq!(validate_outline(&entries, &rules, None), vec![]);
+        assert_eq!(
+            validate_outline(&entries, &rules, Some(Duration::from_secs(64))),
+            vec![OutlineViolation::ChapterTooShort {
+                entry: entries[3].clone(),
+                length: Duration::from_secs(4),
+                min_length: Duration::from_secs(10),
+            }]
+        );
+        assert_eq!(
+            validate_outline(&entries, &rules, Some(Duration::from_secs(70))),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn requires_enough_chapters() {
+        let entries =
+            parse_outline("0:00 Start\n  1:00 Background").expect("parse_outline should succeed");
+
+        assert_eq!(
+            validate_outline(&entries, &ChapterRules::youtube(), None),
+            vec![OutlineViolation::TooFewChapters {
+                count: 1,
+                min_chapters: 3
+            }]
+        );
+        assert_eq!(
+            validate_outline(&entries, &ChapterRules::podcasting_2_0(), None),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn fails_only_when_strict() {
+        let entries = parse_outline("0:05 Start").expect("parse_outline should succeed");
+        let rules = ChapterRules::spotify();
+
+        assert_eq!(
+            check_outline(&entries, &rules, None, false).unwrap().len(),
+            2
+        );
+        assert_eq!(
+            check_outline(&entries, &rules, None, true)
+                .unwrap_err()
+                .violations
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn parses_rule_set_names() {
+        assert_eq!(
+            "YouTube".parse::<ChapterRules>().unwrap(),
+            ChapterRules::youtube()
+        );
+        assert_eq!(
+            "podcasting2.0".parse::<ChapterRules>().unwrap(),
+            ChapterRules::podcasting_2_0()
+        );
+        assert!("vimeo".parse::<ChapterRules>().is_err());
+    }
+}
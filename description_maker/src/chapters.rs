@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use common::export_chapters::{Cue, ExportChaptersError};
+use common::{ChapterExporter, ChapterFormat, OutlineEntry, PodcastChapters};
+
+use crate::error::DescriptionError;
+use crate::types::{EpisodeInfo, PodcastInfo};
+
+/// Generates a Podcasting 2.0 chapters file for the outline, titled from the
+/// podcast and episode when they are given.
+pub fn generate_chapters_json(
+    outline: &[OutlineEntry],
+    podcast_info: Option<&PodcastInfo>,
+    episode: Option<&EpisodeInfo>,
+) -> Result<String, DescriptionError> {
+    let mut chapters = PodcastChapters::from_outline(outline);
+    chapters.podcast_name = podcast_info.map(|podcast_info| podcast_info.name.clone());
+    chapters.title = episode.map(|episode| episode.title.clone());
+
+    let json = chapters
+        .to_json()
+        .map_err(|e| DescriptionError::Serialize(e.to_string()))?;
+    Ok(json + "\n")
+}
+
+/// Writes the outline as chapters in another format, such as WebVTT for a
+/// web player or FFmetadata for muxing with ffmpeg.
+///
+/// `cue_file` is the audio file a CUE sheet points at.
+pub fn export_chapters(
+    outline: &[OutlineEntry],
+    format: ChapterFormat,
+    duration: Option<Duration>,
+    cue_file: &str,
+) -> Result<String, ExportChaptersError> {
+    let exporter: Box<dyn ChapterExporter> = match format {
+        ChapterFormat::Cue => Box::new(Cue {
+            file_name: cue_file.to_string(),
+        }),
+        format => format.exporter(),
+    };
+    exporter.export(outline, duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::parse_outline;
+
+    #[test]
+    fn titles_chapters_from_the_podcast_and_episode() {
+        let outline = parse_outline("0:00 Start\n1:10 Robots").unwrap();
+        let episode = EpisodeInfo::starter();
+
+        let json = generate_chapters_json(&outline, Some(&PodcastInfo::starter()), Some(&episode))
+            .expect("generate_chapters_json should succeed");
+
+        assert!(json.contains("\"podcastName\": \"Your great podcast\""));
+        assert!(json.contains("\"title\": \"Hello, world!\""));
+        assert!(json.contains("\"title\": \"Robots\""));
+    }
+}
@@ -0,0 +1,96 @@
+use std::path::PathBuf;
+
+use common::audio::AudioError;
+use common::export_chapters::ExportChaptersError;
+use common::id3_chapters::Id3ChaptersError;
+use common::mp4_chapters::Mp4ChaptersError;
+use common::read_outline::ReadOutlineError;
+use common::validate_outline::OutlineViolations;
+use common::{ErrorKind, FileError};
+use description_maker::DescriptionError;
+
+/// Why a `description_maker` command failed.
+#[derive(Debug)]
+pub enum CliError {
+    File(FileError),
+    Outline(ReadOutlineError),
+    ChapterRules(OutlineViolations),
+    Description(DescriptionError),
+    Audio(PathBuf, AudioError),
+    Id3(PathBuf, Id3ChaptersError),
+    Mp4(PathBuf, Mp4ChaptersError),
+    /// Chapters can only be embedded in MP3 and MP4 files.
+    UnsupportedContainer(PathBuf),
+}
+
+impl CliError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            CliError::File(e) => e.kind(),
+            CliError::Outline(e) => e.kind(),
+            CliError::ChapterRules(e) => e.kind(),
+            CliError::Description(e) => e.kind(),
+            CliError::Audio(_, e) => e.kind(),
+            CliError::Id3(_, e) => e.kind(),
+            CliError::Mp4(_, e) => e.kind(),
+            CliError::UnsupportedContainer(_) => ErrorKind::Media,
+        }
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::File(e) => write!(f, "{}", e),
+            CliError::Outline(e) => write!(f, "{}", e),
+            CliError::ChapterRules(e) => write!(f, "{}", e),
+            CliError::Description(e) => write!(f, "{}", e),
+            CliError::Audio(path, e) => write!(f, "{}: {}", path.display(), e),
+            CliError::Id3(path, e) => write!(f, "{}: {}", path.display(), e),
+            CliError::Mp4(path, e) => write!(f, "{}: {}", path.display(), e),
+            CliError::UnsupportedContainer(path) => write!(
+                f,
+                "{}: Chapters can only be embedded in MP3, M4A and MP4 files",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<FileError> for CliError {
+    fn from(e: FileError) -> Self {
+        CliError::File(e)
+    }
+}
+
+impl From<ReadOutlineError> for CliError {
+    fn from(e: ReadOutlineError) -> Self {
+        CliError::Outline(e)
+    }
+}
+
+impl From<OutlineViolations> for CliError {
+    fn from(e: OutlineViolations) -> Self {
+        CliError::ChapterRules(e)
+    }
+}
+
+impl From<DescriptionError> for CliError {
+    fn from(e: DescriptionError) -> Self {
+        CliError::Description(e)
+    }
+}
+
+impl From<serde_yaml::Error> for CliError {
+    fn from(e: serde_yaml::Error) -> Self {
+        CliError::Description(e.into())
+    }
+}
+
+impl From<ExportChaptersError> for CliError {
+    fn from(e: ExportChaptersError) -> Self {
+        CliError::Description(e.into())
+    }
+}
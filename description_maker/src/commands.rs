@@ -0,0 +1,230 @@
+use std::path::Path;
+use std::time::Duration;
+
+use common::audio::read_audio_info;
+use common::validate_outline::OutlineViolation;
+use common::{
+    check_outline, read_id3_chapters, read_mp4_chapters, read_outline, read_yaml, write_file,
+    write_id3_chapters, write_mp4_chapters, ChapterFormat, ChapterRules, FileError, Id3Version,
+    Mp4ChapterKinds, OutlineEntry, OutlineOptions,
+};
+
+use description_maker::types::OUTLINE_STARTER;
+use description_maker::{
+    export_chapters, generate_chapters_json, generate_content_markdown, EpisodeInfo, PodcastInfo,
+};
+
+use crate::cli_error::CliError;
+
+pub fn make_podcast_info_starter(save_path: &Path) -> Result<(), CliError> {
+    make_sure_parent_dir_exists(save_path)?;
+
+    let podcast_yaml = serde_yaml::to_string(&PodcastInfo::starter())?;
+    write_file(save_path, podcast_yaml)?;
+
+    Ok(())
+}
+
+pub fn make_episode_starter(save_path: &Path) -> Result<(), CliError> {
+    make_sure_parent_dir_exists(save_path)?;
+
+    let episode_yaml = serde_yaml::to_string(&EpisodeInfo::starter())?;
+    write_file(save_path, episode_yaml)?;
+
+    Ok(())
+}
+
+pub fn make_outline_starter(save_path: &Path) -> Result<(), CliError> {
+    make_sure_parent_dir_exists(save_path)?;
+
+    write_file(save_path, OUTLINE_STARTER)?;
+
+    Ok(())
+}
+
+/// Reads an episode file, along with the length and details of its audio if
+/// it has an `audio_path`, which is relative to the episode file.
+fn read_episode(episode_path: &Path) -> Result<EpisodeInfo, CliError> {
+    let mut episode = read_yaml::<EpisodeInfo>(episode_path)?;
+    if let Some(audio_path) = &episode.audio_path {
+        let audio_path = episode_path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(audio_path);
+        let audio =
+            read_audio_info(&audio_path).map_err(|e| CliError::Audio(audio_path.clone(), e))?;
+        episode.set_audio(audio);
+    }
+    Ok(episode)
+}
+
+fn make_sure_parent_dir_exists(path: &Path) -> Result<(), CliError> {
+    if let Some(parent) = path.parent() {
+        if !parent.exists() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| FileError::Write(parent.to_path_buf(), e))?;
+        }
+    }
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn make_markdown(
+    podcast_path: &Path,
+    episode_path: &Path,
+    outline_path: &Path,
+    out_file_path: &Path,
+    outline_options: &OutlineOptions,
+    chapter_rules: &ChapterRules,
+    strict: bool,
+    episode_duration: Option<Duration>,
+) -> Result<Vec<OutlineViolation>, CliError> {
+    let podcast_info = read_yaml::<PodcastInfo>(podcast_path)?;
+    let episode = read_episode(episode_path)?;
+    let outline = read_outline(outline_path, outline_options)?;
+    let violations = check_outline(
+        &outline,
+        chapter_rules,
+        episode_duration.or(episode.audio.map(|audio| audio.duration)),
+        strict,
+    )?;
+
+    let content_md =
+        generate_content_markdown(&podcast_info, &episode, &outline, episode_duration)?;
+    make_sure_parent_dir_exists(out_file_path)?;
+    write_file(out_file_path, content_md)?;
+
+    Ok(violations)
+}
+
+/// Writes a Podcasting 2.0 chapters file for the outline, titled from the
+/// podcast and episode files when they are given.
+pub fn make_chapters(
+    outline_path: &Path,
+    podcast_path: Option<&Path>,
+    episode_path: Option<&Path>,
+    out_file_path: &Path,
+    outline_options: &OutlineOptions,
+    strict: bool,
+) -> Result<Vec<OutlineViolation>, CliError> {
+    let outline = read_outline(outline_path, outline_options)?;
+    let podcast_info = podcast_path.map(read_yaml::<PodcastInfo>).transpose()?;
+    let episode = episode_path.map(read_episode).transpose()?;
+    let episode_duration = episode
+        .as_ref()
+        .and_then(|episode| episode.audio)
+        .map(|audio| audio.duration);
+    let violations = check_outline(
+        &outline,
+        &ChapterRules::podcasting_2_0(),
+        episode_duration,
+        strict,
+    )?;
+
+    let json = generate_chapters_json(&outline, podcast_info.as_ref(), episode.as_ref())?;
+    write_file(out_file_path, json)?;
+    Ok(violations)
+}
+
+/// The containers chapters can be embedded in.
+enum AudioContainer {
+    Mp3,
+    Mp4,
+}
+
+impl AudioContainer {
+    fn from_path(path: &Path) -> Result<AudioContainer, CliError> {
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase());
+        match extension.as_deref() {
+            Some("mp3") => Ok(AudioContainer::Mp3),
+            Some("m4a" | "m4b" | "mp4" | "m4v" | "mov") => Ok(AudioContainer::Mp4),
+            _ => Err(CliError::UnsupportedContainer(path.to_path_buf())),
+        }
+    }
+}
+
+/// Writes the outline into an MP3's ID3 tag, or into an M4A or MP4 file's
+/// chapter track and chapter list.
+pub fn write_audio_chapters(
+    audio_path: &Path,
+    outline_path: &Path,
+    outline_options: &OutlineOptions,
+    id3_version: Id3Version,
+    mp4_chapters: Mp4ChapterKinds,
+) -> Result<(), CliError> {
+    let container = AudioContainer::from_path(audio_path)?;
+    let outline = read_outline(outline_path, outline_options)?;
+    match container {
+        AudioContainer::Mp3 => write_id3_chapters(audio_path, &outline, id3_version)
+            .map_err(|e| CliError::Id3(audio_path.to_path_buf(), e)),
+        AudioContainer::Mp4 => write_mp4_chapters(audio_path, &outline, mp4_chapters)
+            .map_err(|e| CliError::Mp4(audio_path.to_path_buf(), e)),
+    }
+}
+
+/// Reads the chapters embedded in an MP3, M4A or MP4 file.
+pub fn read_audio_chapters(audio_path: &Path) -> Result<Vec<OutlineEntry>, CliError> {
+    match AudioContainer::from_path(audio_path)? {
+        AudioContainer::Mp3 => {
+            read_id3_chapters(audio_path).map_err(|e| CliError::Id3(audio_path.to_path_buf(), e))
+        }
+        AudioContainer::Mp4 => {
+            read_mp4_chapters(audio_path).map_err(|e| CliError::Mp4(audio_path.to_path_buf(), e))
+        }
+    }
+}
+
+/// Writes the outline as chapters in another format.
+pub fn export_chapters_file(
+    outline_path: &Path,
+    out_file_path: &Path,
+    outline_options: &OutlineOptions,
+    format: ChapterFormat,
+    duration: Option<Duration>,
+    cue_file: &str,
+) -> Result<(), CliError> {
+    let outline = read_outline(outline_path, outline_options)?;
+    write_file(
+        out_file_path,
+        export_chapters(&outline, format, duration, cue_file)?,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::TimeCode;
+    use std::path::PathBuf;
+
+    #[test]
+    fn reads_audio_relative_to_the_episode_file() {
+        let dir = std::env::temp_dir().join(format!("episode_audio_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("audio")).unwrap();
+        let mut wav = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+        wav.extend(16u32.to_le_bytes());
+        wav.extend([1, 0, 1, 0]);
+        wav.extend(8_000u32.to_le_bytes());
+        wav.extend(16_000u32.to_le_bytes());
+        wav.extend([2, 0, 16, 0]);
+        wav.extend(b"data");
+        wav.extend(32_000u32.to_le_bytes());
+        wav.extend(vec![0; 32_000]);
+        std::fs::write(dir.join("audio").join("episode.wav"), wav).unwrap();
+        let episode = EpisodeInfo {
+            audio_path: Some(PathBuf::from("audio/episode.wav")),
+            ..EpisodeInfo::starter()
+        };
+        let episode_path = dir.join("episode.yaml");
+        std::fs::write(&episode_path, serde_yaml::to_string(&episode).unwrap()).unwrap();
+
+        let result = read_episode(&episode_path);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let episode = result.expect("read_episode should succeed");
+        assert_eq!(episode.duration, TimeCode::new(0, 0, 2).ok());
+        assert_eq!(episode.audio.and_then(|audio| audio.channels), Some(1));
+    }
+}
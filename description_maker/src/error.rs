@@ -0,0 +1,60 @@
+use common::chapter::ChapterError;
+use common::export_chapters::ExportChaptersError;
+use common::ErrorKind;
+
+/// Why generating an episode's description or chapters failed.
+#[derive(Debug)]
+pub enum DescriptionError {
+    Chapter(ChapterError),
+    Export(ExportChaptersError),
+    Template(askama::Error),
+    /// A starter file or chapters file couldn't be serialized.
+    Serialize(String),
+}
+
+impl DescriptionError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            DescriptionError::Chapter(e) => e.kind(),
+            DescriptionError::Export(e) => e.kind(),
+            DescriptionError::Template(_) | DescriptionError::Serialize(_) => ErrorKind::Template,
+        }
+    }
+}
+
+impl std::fmt::Display for DescriptionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DescriptionError::Chapter(e) => write!(f, "{}", e),
+            DescriptionError::Export(e) => write!(f, "{}", e),
+            DescriptionError::Template(e) => write!(f, "Could not render the template: {}", e),
+            DescriptionError::Serialize(message) => write!(f, "Could not serialize: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for DescriptionError {}
+
+impl From<ChapterError> for DescriptionError {
+    fn from(e: ChapterError) -> Self {
+        DescriptionError::Chapter(e)
+    }
+}
+
+impl From<ExportChaptersError> for DescriptionError {
+    fn from(e: ExportChaptersError) -> Self {
+        DescriptionError::Export(e)
+    }
+}
+
+impl From<askama::Error> for DescriptionError {
+    fn from(e: askama::Error) -> Self {
+        DescriptionError::Template(e)
+    }
+}
+
+impl From<serde_yaml::Error> for DescriptionError {
+    fn from(e: serde_yaml::Error) -> Self {
+        DescriptionError::Serialize(e.to_string())
+    }
+}
@@ -0,0 +1,18 @@
+//! Generates the description, chapters and links for publishing a podcast
+//! episode.
+//!
+//! The functions here take podcast and episode details and outlines that have
+//! already been read, and return the generated text, so reading and writing
+//! files is left to the caller.
+
+pub mod error;
+pub use error::DescriptionError;
+
+pub mod types;
+pub use types::{EpisodeInfo, Link, Noun, PodcastInfo};
+
+pub mod template;
+pub use template::generate_content_markdown;
+
+pub mod chapters;
+pub use chapters::{export_chapters, generate_chapters_json};
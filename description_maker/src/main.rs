@@ -1,23 +1,37 @@
-use clap::{arg, command, value_parser, Command};
+use clap::{arg, command, value_parser, ArgMatches, Command};
 use std::path::PathBuf;
 
-mod template;
-use crate::template::{
-    generate_content_markdown, make_episode_starter, make_outline_starter,
-    make_podcast_info_starter,
+mod cli_error;
+use cli_error::CliError;
+
+mod commands;
+use crate::commands::{
+    export_chapters_file, make_chapters, make_episode_starter, make_markdown, make_outline_starter,
+    make_podcast_info_starter, read_audio_chapters, write_audio_chapters,
 };
 
-mod types;
+use common::cli::{
+    audio_path_arg, duration_arg, fcpxml_markers_arg, frame_rate_arg, get_episode_duration,
+    get_outline_options, outline_format_arg,
+};
+use common::{format_outline, ChapterFormat, ChapterRules, Id3Version, Mp4ChapterKinds};
 
 fn main() {
     let matches = cmd().get_matches();
 
+    if let Err(e) = run(&matches) {
+        eprintln!("{}", e.to_string().trim_end());
+        std::process::exit(e.kind().exit_code());
+    }
+}
+
+fn run(matches: &ArgMatches) -> Result<(), CliError> {
     match matches.subcommand() {
         Some(("new_podcast_config", sub_matches)) => {
             let save_path: &PathBuf = sub_matches
                 .get_one("output_path")
                 .expect("A directory was provided");
-            make_podcast_info_starter(&save_path).expect("Make podcast info config");
+            make_podcast_info_starter(save_path)?;
             println!("Starter podcast file generated: {}", save_path.display());
         }
         Some(("new_episode", sub_matches)) => {
@@ -26,8 +40,8 @@ fn main() {
                 .expect("A directory was provided");
             let episode_path = save_dir.join("episode.yaml");
             let outline_path = save_dir.join("outline.txt");
-            make_episode_starter(&episode_path).expect("Make episode info");
-            make_outline_starter(&outline_path).expect("Make outline info");
+            make_episode_starter(&episode_path)?;
+            make_outline_starter(&outline_path)?;
 
             println!("Starter episode files generated: {}", save_dir.display())
         }
@@ -44,14 +58,123 @@ fn main() {
             let out_file_path: &PathBuf = sub_matches
                 .get_one("out_file_path")
                 .expect("An output file was provided");
+            let outline_options = get_outline_options(sub_matches);
+            let chapter_rules: &ChapterRules = sub_matches
+                .get_one("chapter_rules")
+                .expect("Chapter rules were provided");
+            let strict = sub_matches.get_flag("strict");
+            let episode_duration = get_episode_duration(sub_matches, CliError::Audio)?;
 
-            generate_content_markdown(podcast_path, episode_path, outline_path, out_file_path)
-                .expect("Markdown to generate");
+            let violations = make_markdown(
+                podcast_path,
+                episode_path,
+                outline_path,
+                out_file_path,
+                &outline_options,
+                chapter_rules,
+                strict,
+                episode_duration,
+            )?;
+            for violation in violations {
+                eprintln!("Warning: {}", violation);
+            }
 
             println!("Markdown generated: {}", out_file_path.display());
         }
+        Some(("make_chapters", sub_matches)) => {
+            let outline_path: &PathBuf = sub_matches
+                .get_one("outline_path")
+                .expect("A time codes file was provided");
+            let out_file_path: &PathBuf = sub_matches
+                .get_one("out_file_path")
+                .expect("An output file was provided");
+            let podcast_path = sub_matches
+                .get_one::<PathBuf>("podcast_path")
+                .map(PathBuf::as_path);
+            let episode_path = sub_matches
+                .get_one::<PathBuf>("episode_path")
+                .map(PathBuf::as_path);
+            let outline_options = get_outline_options(sub_matches);
+            let strict = sub_matches.get_flag("strict");
+
+            let violations = make_chapters(
+                outline_path,
+                podcast_path,
+                episode_path,
+                out_file_path,
+                &outline_options,
+                strict,
+            )?;
+            for violation in violations {
+                eprintln!("Warning: {}", violation);
+            }
+
+            println!("Chapters generated: {}", out_file_path.display());
+        }
+        Some(("write_chapters", sub_matches)) => {
+            let audio_path: &PathBuf = sub_matches
+                .get_one("audio_path")
+                .expect("An audio file was provided");
+            let outline_path: &PathBuf = sub_matches
+                .get_one("outline_path")
+                .expect("A time codes file was provided");
+            let id3_version: &Id3Version = sub_matches
+                .get_one("id3_version")
+                .expect("An ID3 version was provided");
+            let mp4_chapters: &Mp4ChapterKinds = sub_matches
+                .get_one("mp4_chapters")
+                .expect("MP4 chapter kinds were provided");
+            let outline_options = get_outline_options(sub_matches);
+
+            write_audio_chapters(
+                audio_path,
+                outline_path,
+                &outline_options,
+                *id3_version,
+                *mp4_chapters,
+            )?;
+
+            println!("Chapters written: {}", audio_path.display());
+        }
+        Some(("read_chapters", sub_matches)) => {
+            let audio_path: &PathBuf = sub_matches
+                .get_one("audio_path")
+                .expect("An audio file was provided");
+
+            let entries = read_audio_chapters(audio_path)?;
+            print!("{}", format_outline(&entries));
+        }
+        Some(("export_chapters", sub_matches)) => {
+            let outline_path: &PathBuf = sub_matches
+                .get_one("outline_path")
+                .expect("A time codes file was provided");
+            let format: &ChapterFormat = sub_matches
+                .get_one("format")
+                .expect("A chapter format was provided");
+            let out_file_path = match sub_matches.get_one::<PathBuf>("out_file_path") {
+                Some(out_file_path) => out_file_path.clone(),
+                None => PathBuf::from(format!("chapters.{}", format.extension())),
+            };
+            let duration = get_episode_duration(sub_matches, CliError::Audio)?;
+            let cue_file: &String = sub_matches
+                .get_one("cue_file")
+                .expect("A CUE audio file name was provided");
+            let outline_options = get_outline_options(sub_matches);
+
+            export_chapters_file(
+                outline_path,
+                &out_file_path,
+                &outline_options,
+                *format,
+                duration,
+                cue_file,
+            )?;
+
+            println!("Chapters exported: {}", out_file_path.display());
+        }
         _ => unreachable!("Subcommand should be provided"),
     }
+    Ok(())
 }
 
 fn cmd() -> Command {
@@ -102,7 +225,7 @@ fn cmd() -> Command {
                 )
                 .arg(
                     arg!(
-                        <outline_path> "Path to the outline file"
+                        <outline_path> "Path to the outline file or marker export"
                     )
                     .required(true)
                     .value_parser(value_parser!(PathBuf)),
@@ -113,8 +236,146 @@ fn cmd() -> Command {
                     )
                     .default_value("content.md")
                     .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(outline_format_arg())
+                .arg(frame_rate_arg())
+                .arg(fcpxml_markers_arg())
+                .arg(
+                    arg!(
+                        --chapter_rules <rules> "Chapter rules to check the outline against: youtube, spotify or podcasting2.0"
+                    )
+                    .default_value("youtube")
+                    .value_parser(value_parser!(ChapterRules)),
+                )
+                .arg(
+                    arg!(
+                        --strict "Fail instead of warning when the outline breaks the chapter rules"
+                    ),
+                )
+                .arg(duration_arg("Length of the episode, to show how long each chapter lasts"))
+                .arg(audio_path_arg()),
+        )
+        .subcommand(
+            Command::new("make_chapters")
+                .about("Generates a Podcasting 2.0 chapters JSON file from an outline")
+                .arg(
+                    arg!(
+                        <outline_path> "Path to the outline file or marker export"
+                    )
+                    .required(true)
+                    .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(
+                        -o --out_file_path <file> "Path for where to save the chapters file"
+                    )
+                    .default_value("chapters.json")
+                    .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(
+                        --podcast_path <file> "Path to the podcast file, to add the podcast name"
+                    )
+                    .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(
+                        --episode_path <file> "Path to the episode file, to add the episode title"
+                    )
+                    .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(outline_format_arg())
+                .arg(frame_rate_arg())
+                .arg(fcpxml_markers_arg())
+                .arg(
+                    arg!(
+                        --strict "Fail instead of warning when the outline breaks the Podcasting 2.0 chapter rules"
+                    ),
+                ),
+        )
+        .subcommand(
+            Command::new("write_chapters")
+                .about("Writes the outline into an MP3, M4A or MP4 file as chapters")
+                .arg(
+                    arg!(
+                        <audio_path> "Path to the episode MP3, M4A or MP4"
+                    )
+                    .required(true)
+                    .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(
+                        <outline_path> "Path to the outline file or marker export"
+                    )
+                    .required(true)
+                    .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(
+                        --id3_version <version> "ID3 version to save the tag as: 2.3 or 2.4"
+                    )
+                    .default_value("2.3")
+                    .value_parser(value_parser!(Id3Version)),
+                )
+                .arg(
+                    arg!(
+                        --mp4_chapters <kinds> "Where to store chapters in an M4A or MP4 file: track, list or both"
+                    )
+                    .default_value("both")
+                    .value_parser(value_parser!(Mp4ChapterKinds)),
+                )
+                .arg(outline_format_arg())
+                .arg(frame_rate_arg())
+                .arg(fcpxml_markers_arg()),
+        )
+        .subcommand(
+            Command::new("read_chapters")
+                .about("Prints the chapters of an MP3, M4A or MP4 file as an outline")
+                .arg(
+                    arg!(
+                        <audio_path> "Path to the episode MP3, M4A or MP4"
+                    )
+                    .required(true)
+                    .value_parser(value_parser!(PathBuf)),
                 ),
         )
+        .subcommand(
+            Command::new("export_chapters")
+                .about("Exports the outline as WebVTT, FFmetadata, Matroska XML, CUE or OGM chapters")
+                .arg(
+                    arg!(
+                        <outline_path> "Path to the outline file or marker export"
+                    )
+                    .required(true)
+                    .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(
+                        -f --format <format> "Chapter format: webvtt, ffmetadata, matroska, cue or ogm"
+                    )
+                    .required(true)
+                    .value_parser(value_parser!(ChapterFormat)),
+                )
+                .arg(
+                    arg!(
+                        -o --out_file_path <file> "Path for where to save the chapters, chapters.<extension> if not given"
+                    )
+                    .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(duration_arg(
+                    "Length of the episode, which WebVTT and FFmetadata need to end the last chapter",
+                ))
+                .arg(audio_path_arg())
+                .arg(
+                    arg!(
+                        --cue_file <file> "Name of the audio file a CUE sheet refers to"
+                    )
+                    .default_value("episode.wav"),
+                )
+                .arg(outline_format_arg())
+                .arg(frame_rate_arg())
+                .arg(fcpxml_markers_arg()),
+        )
 }
 
 #[test]
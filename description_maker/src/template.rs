@@ -1,18 +1,14 @@
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use askama::Template;
-use serde_yaml;
 
-use crate::types::{EpisodeInfo, Link, Noun, PodcastInfo};
+use crate::error::DescriptionError;
+use crate::types::{EpisodeInfo, Noun, PodcastInfo};
 
-use common::{parse_outline, OutlineEntry};
+use common::{slugify, Chapter, OutlineEntry};
 
 pub fn and_names_on_x(nouns: &[Noun]) -> String {
-    let names = nouns
-        .iter()
-        .map(|noun| get_name_on_x(noun))
-        .collect::<Vec<String>>();
+    let names = nouns.iter().map(get_name_on_x).collect::<Vec<String>>();
     and_items(&names)
 }
 
@@ -45,13 +41,10 @@ pub fn get_name_on_x(noun: &Noun) -> String {
     }
 }
 
+/// The episode's number and title as a slug, like `1-hello-world`, for the
+/// file name of its transcript page.
 pub fn get_episode_slug(episode: &EpisodeInfo) -> String {
-    format!("{} {}", episode.number, episode.title)
-        .to_lowercase()
-        .replace(" ", "-")
-        .chars()
-        .filter(|c| c.is_alphanumeric() || *c == '-')
-        .collect::<String>()
+    slugify(&format!("{} {}", episode.number, episode.title))
 }
 
 fn get_transcript_url(episode: &EpisodeInfo, podcast_info: &PodcastInfo) -> String {
@@ -59,6 +52,47 @@ fn get_transcript_url(episode: &EpisodeInfo, podcast_info: &PodcastInfo) -> Stri
     format!("{}/{}.html", podcast_info.transcript_site_url, episode_slug)
 }
 
+/// The chapters of an outline without their nested sub-topics or hidden
+/// chapters.
+pub fn top_level(outline: &[OutlineEntry]) -> Vec<OutlineEntry> {
+    outline
+        .iter()
+        .filter(|entry| entry.level == 0 && !entry.hidden)
+        .cloned()
+        .collect()
+}
+
+/// The entries of an outline that are shown in published chapter lists.
+pub fn visible(outline: &[OutlineEntry]) -> Vec<OutlineEntry> {
+    outline
+        .iter()
+        .filter(|entry| !entry.hidden)
+        .cloned()
+        .collect()
+}
+
+/// Pairs each outline entry, in the order it was written, with how long it
+/// lasts, like ` (12 min)`, or nothing if the episode's length isn't known.
+///
+/// `chapters` are in time order, so they are matched to entries by sorting
+/// the entries' indices the same way.
+fn with_lengths(outline: &[OutlineEntry], chapters: &[Chapter]) -> Vec<(OutlineEntry, String)> {
+    let mut lengths = vec![String::new(); outline.len()];
+    if !chapters.is_empty() {
+        let mut order = (0..outline.len()).collect::<Vec<usize>>();
+        order.sort_by(|a, b| outline[*a].cmp(&outline[*b]));
+        for (index, chapter) in order.into_iter().zip(chapters) {
+            lengths[index] = format!(" ({})", chapter.length_label());
+        }
+    }
+    outline.iter().cloned().zip(lengths).collect()
+}
+
+/// Repeats `unit` once for each level an outline entry is nested.
+pub fn indent(entry: &OutlineEntry, unit: &str) -> String {
+    unit.repeat(entry.level)
+}
+
 fn prepare_html(text: &str) -> String {
     text.replace("\n", "<br/>")
 }
@@ -77,96 +111,34 @@ struct ContentTemplate {
     episode: EpisodeInfo,
     podcast_info: PodcastInfo,
     spotify_html: String,
-    outline: Vec<OutlineEntry>,
-}
-
-pub fn make_podcast_info_starter(save_path: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-    make_sure_parent_dir_exists(save_path)?;
-
-    let podcast_info = PodcastInfo {
-        name: "Your great podcast".to_string(),
-        transcript_site_url: "https://www.ygp.com/transcripts".to_string(),
-        links: vec![
-            Link {
-                text: "LinkedIn".to_string(),
-                href: "https://www.linkedin.com/in/ygp/".to_string(),
-            },
-            Link {
-                text: "Website".to_string(),
-                href: "https://www.ygp.com/".to_string(),
-            },
-        ],
-    };
-
-    let podcast_yaml = serde_yaml::to_string(&podcast_info)?;
-    std::fs::write(save_path, podcast_yaml)?;
-
-    Ok(())
-}
-
-pub fn make_episode_starter(save_path: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-    make_sure_parent_dir_exists(save_path)?;
-
-    let episode = EpisodeInfo {
-        description: "Your great episode\non multiple lines.".to_string(),
-        title: "Hello, world!".to_string(),
-        number: 1,
-        links: vec![Link {
-            text: "Company's LinkedIn".to_string(),
-            href: "https://www.company.com/".to_string(),
-        }],
-        guests: vec![Noun {
-            name: "Foo Bar".to_string(),
-            x_handle: Some("foo".to_string()),
-        }],
-        organization: Some(Noun {
-            name: "Company".to_string(),
-            x_handle: Some("company".to_string()),
-        }),
-        youtube_video_url: None,
-        x_post_url: None,
-        spotify_for_podcasters_url: None,
-    };
-
-    let episode_yaml = serde_yaml::to_string(&episode)?;
-    std::fs::write(save_path, episode_yaml)?;
-
-    Ok(())
-}
-
-pub fn make_outline_starter(save_path: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-    make_sure_parent_dir_exists(save_path)?;
-
-    let text = "00:00:00 - TODO: ADD OUTLINE ENTRY\n";
-    std::fs::write(save_path, text)?;
-
-    Ok(())
-}
-
-fn make_sure_parent_dir_exists(path: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-    let path = Path::new(path);
-    if let Some(parent) = path.parent() {
-        if !parent.exists() {
-            fs::create_dir_all(parent)?;
-        }
-    }
-    Ok(())
+    /// Every outline entry with its length, or nothing if the episode's
+    /// length isn't known.
+    outline: Vec<(OutlineEntry, String)>,
+    top_level_outline: Vec<OutlineEntry>,
 }
 
+/// Renders the Markdown with everything needed to publish an episode: its
+/// title and description for each platform, its chapters, and links.
+///
+/// Chapter lengths are shown when `episode_duration` is given or the episode's
+/// audio was read, and an entry that starts after the episode ends is an
+/// error.
 pub fn generate_content_markdown(
-    podcast_path: &PathBuf,
-    episode_path: &PathBuf,
-    outline_path: &PathBuf,
-    out_file_path: &PathBuf,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let podcast_info = serde_yaml::from_str::<PodcastInfo>(&fs::read_to_string(podcast_path)?)?;
-    let episode = serde_yaml::from_str::<EpisodeInfo>(&fs::read_to_string(episode_path)?)?;
-    let outline = parse_outline(&fs::read_to_string(outline_path)?)?;
+    podcast_info: &PodcastInfo,
+    episode: &EpisodeInfo,
+    outline: &[OutlineEntry],
+    episode_duration: Option<Duration>,
+) -> Result<String, DescriptionError> {
+    let episode_duration = episode_duration.or(episode.audio.map(|audio| audio.duration));
+    let chapters = match episode_duration {
+        Some(episode_duration) => Chapter::from_outline(outline, episode_duration)?,
+        None => vec![],
+    };
 
     let template = SpotifyTemplate {
         episode: episode.clone(),
         podcast_info: podcast_info.clone(),
-        outline: outline.clone(),
+        outline: visible(outline),
     };
 
     let spotify_html = template.render()?;
@@ -176,11 +148,60 @@ pub fn generate_content_markdown(
         episode: episode.clone(),
         podcast_info: podcast_info.clone(),
         spotify_html,
-        outline: outline.clone(),
+        outline: with_lengths(outline, &chapters),
+        top_level_outline: top_level(outline),
     };
-    let content_md = content_template.render()?;
-    make_sure_parent_dir_exists(out_file_path)?;
-    std::fs::write(out_file_path, content_md)?;
+    Ok(content_template.render()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::parse_outline;
+
+    #[test]
+    fn generates_markdown_without_files() {
+        let outline = parse_outline("0:00 Start\n  0:30 Background\n1:10 Robots").unwrap();
+
+        let markdown = generate_content_markdown(
+            &PodcastInfo::starter(),
+            &EpisodeInfo::starter(),
+            &outline,
+            Some(Duration::from_secs(120)),
+        )
+        .expect("generate_content_markdown should succeed");
+
+        assert!(markdown.starts_with("# Ep. 1: Hello, world!\n"));
+        assert!(markdown.contains("```\n1-hello-world\n```"));
+        assert!(markdown
+            .contains("0:00 Start (1 min)\n  0:30 Background (40 sec)\n1:10 Robots (50 sec)"));
+    }
 
-    Ok(())
+    #[test]
+    fn pairs_lengths_with_repeated_entries() {
+        let outline = parse_outline("0:00 Questions\n0:30 Demo\n1:00 Questions").unwrap();
+        let chapters = Chapter::from_outline(&outline, Duration::from_secs(240)).unwrap();
+
+        assert_eq!(
+            with_lengths(&outline, &chapters)
+                .into_iter()
+                .map(|(entry, length)| format!("{}{}", entry.text, length))
+                .collect::<Vec<String>>(),
+            vec!["Questions (30 sec)", "Demo (30 sec)", "Questions (3 min)"]
+        );
+    }
+
+    #[test]
+    fn rejects_chapters_after_the_episode() {
+        let outline = parse_outline("0:00 Start\n3:00 Too late").unwrap();
+
+        let result = generate_content_markdown(
+            &PodcastInfo::starter(),
+            &EpisodeInfo::starter(),
+            &outline,
+            Some(Duration::from_secs(120)),
+        );
+
+        assert!(matches!(result, Err(DescriptionError::Chapter(_))));
+    }
 }
@@ -1,3 +1,8 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use common::audio::AudioInfo;
+use common::TimeCode;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -23,6 +28,53 @@ pub struct EpisodeInfo {
     pub youtube_video_url: Option<String>,
     pub x_post_url: Option<String>,
     pub spotify_for_podcasters_url: Option<String>,
+    /// The episode's audio, relative to the episode file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_path: Option<PathBuf>,
+    /// The length of the episode to the nearest second, read from `audio_path`.
+    #[serde(skip)]
+    pub duration: Option<TimeCode>,
+    /// The technical details of the episode's audio, read from `audio_path`.
+    #[serde(skip)]
+    pub audio: Option<AudioInfo>,
+}
+
+impl EpisodeInfo {
+    /// An example episode to fill in.
+    pub fn starter() -> EpisodeInfo {
+        EpisodeInfo {
+            description: "Your great episode\non multiple lines.".to_string(),
+            title: "Hello, world!".to_string(),
+            number: 1,
+            links: vec![Link {
+                text: "Company's LinkedIn".to_string(),
+                href: "https://www.company.com/".to_string(),
+            }],
+            guests: vec![Noun {
+                name: "Foo Bar".to_string(),
+                x_handle: Some("foo".to_string()),
+            }],
+            organization: Some(Noun {
+                name: "Company".to_string(),
+                x_handle: Some("company".to_string()),
+            }),
+            youtube_video_url: None,
+            x_post_url: None,
+            spotify_for_podcasters_url: None,
+            audio_path: None,
+            duration: None,
+            audio: None,
+        }
+    }
+
+    /// Fills in the length and details of the episode's audio, read from
+    /// `audio_path`.
+    pub fn set_audio(&mut self, audio: AudioInfo) {
+        self.duration = TimeCode::from_duration(Duration::from_secs(
+            audio.duration.as_secs_f64().round() as u64,
+        ));
+        self.audio = Some(audio);
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -31,3 +83,26 @@ pub struct PodcastInfo {
     pub transcript_site_url: String,
     pub links: Vec<Link>,
 }
+
+impl PodcastInfo {
+    /// An example podcast to fill in.
+    pub fn starter() -> PodcastInfo {
+        PodcastInfo {
+            name: "Your great podcast".to_string(),
+            transcript_site_url: "https://www.ygp.com/transcripts".to_string(),
+            links: vec![
+                Link {
+                    text: "LinkedIn".to_string(),
+                    href: "https://www.linkedin.com/in/ygp/".to_string(),
+                },
+                Link {
+                    text: "Website".to_string(),
+                    href: "https://www.ygp.com/".to_string(),
+                },
+            ],
+        }
+    }
+}
+
+/// An example outline to fill in.
+pub const OUTLINE_STARTER: &str = "00:00:00 - TODO: ADD OUTLINE ENTRY\n";
@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+
+use common::audio::AudioError;
+use common::read_outline::ReadOutlineError;
+use common::validate_outline::OutlineViolations;
+use common::{ErrorKind, FileError, OutlineFormat};
+use transcript_marker::TranscriptError;
+
+/// Why a `transcript_marker` command failed.
+#[derive(Debug)]
+pub enum CliError {
+    File(FileError),
+    Outline(ReadOutlineError),
+    ChapterRules(OutlineViolations),
+    Transcript(PathBuf, TranscriptError),
+    Audio(PathBuf, AudioError),
+    NotTextOutline {
+        path: PathBuf,
+        format: OutlineFormat,
+    },
+    NotFormatted(PathBuf),
+}
+
+/// The exit code for an outline that `outline fmt --check` found isn't
+/// formatted, after the codes for each kind of failure.
+const NOT_FORMATTED_EXIT_CODE: i32 = 10;
+
+impl CliError {
+    /// The code to exit with, which is 10 for an outline that needs
+    /// formatting and otherwise depends on the kind of failure.
+    pub fn exit_code(&self) -> i32 {
+        let kind = match self {
+            CliError::File(e) => e.kind(),
+            CliError::Outline(e) => e.kind(),
+            CliError::ChapterRules(e) => e.kind(),
+            CliError::Transcript(_, e) => e.kind(),
+            CliError::Audio(_, e) => e.kind(),
+            CliError::NotTextOutline { .. } => ErrorKind::Outline,
+            CliError::NotFormatted(_) => return NOT_FORMATTED_EXIT_CODE,
+        };
+        kind.exit_code()
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::File(e) => write!(f, "{}", e),
+            CliError::Outline(e) => write!(f, "{}", e),
+            CliError::ChapterRules(e) => write!(f, "{}", e),
+            CliError::Transcript(path, e) => write!(f, "{}: {}", path.display(), e),
+            CliError::Audio(path, e) => write!(f, "{}: {}", path.display(), e),
+            CliError::NotTextOutline { path, format } => write!(
+                f,
+                "{} is read as a {} outline, not a text outline, so it can't be rewritten in place. Give an output file with -o.",
+                path.display(),
+                format
+            ),
+            CliError::NotFormatted(path) => write!(f, "{} is not formatted", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<FileError> for CliError {
+    fn from(e: FileError) -> Self {
+        CliError::File(e)
+    }
+}
+
+impl From<ReadOutlineError> for CliError {
+    fn from(e: ReadOutlineError) -> Self {
+        CliError::Outline(e)
+    }
+}
+
+impl From<OutlineViolations> for CliError {
+    fn from(e: OutlineViolations) -> Self {
+        CliError::ChapterRules(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exits_with_the_documented_codes() {
+        let codes = [
+            (ErrorKind::Io, 3),
+            (ErrorKind::Config, 4),
+            (ErrorKind::Outline, 5),
+            (ErrorKind::Chapters, 6),
+            (ErrorKind::Media, 7),
+            (ErrorKind::Template, 8),
+            (ErrorKind::Transcript, 9),
+        ];
+        for (kind, code) in codes {
+            assert_eq!(kind.exit_code(), code, "{:?}", kind);
+        }
+
+        let not_formatted = CliError::NotFormatted(PathBuf::from("outline.txt"));
+        assert_eq!(not_formatted.exit_code(), 10);
+        let not_text = CliError::NotTextOutline {
+            path: PathBuf::from("markers.csv"),
+            format: OutlineFormat::PremiereCsv,
+        };
+        assert_eq!(not_text.exit_code(), 5);
+    }
+}
@@ -0,0 +1,48 @@
+use common::chapter::ChapterError;
+use common::ErrorKind;
+
+/// A problem marking or shifting a transcript.
+#[derive(Debug)]
+pub enum TranscriptError {
+    InvalidTimestamp { timestamp: String, message: String },
+    InvalidCue { line_number: usize, message: String },
+    InvalidJson(String),
+    Chapter(ChapterError),
+}
+
+impl std::fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TranscriptError::InvalidTimestamp { timestamp, message } => {
+                write!(f, "Invalid transcript timestamp {}: {}", timestamp, message)
+            }
+            TranscriptError::InvalidCue {
+                line_number,
+                message,
+            } => write!(f, "Invalid caption on line {}: {}", line_number, message),
+            TranscriptError::InvalidJson(message) => {
+                write!(f, "Invalid JSON transcript: {}", message)
+            }
+            TranscriptError::Chapter(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TranscriptError {}
+
+impl TranscriptError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            TranscriptError::InvalidTimestamp { .. }
+            | TranscriptError::InvalidCue { .. }
+            | TranscriptError::InvalidJson(_) => ErrorKind::Transcript,
+            TranscriptError::Chapter(e) => e.kind(),
+        }
+    }
+}
+
+impl From<ChapterError> for TranscriptError {
+    fn from(e: ChapterError) -> Self {
+        TranscriptError::Chapter(e)
+    }
+}
@@ -0,0 +1,31 @@
+//! Reads Descript, Whisper, Otter and Riverside transcripts and SRT and
+//! WebVTT captions, marks them with headings and a table of contents from an
+//! outline, and shifts their timestamps after an edit.
+//!
+//! Everything here works on strings and outline entries, so reading and
+//! writing files is left to the caller.
+
+pub mod error;
+pub use error::TranscriptError;
+
+pub mod transcript;
+pub use transcript::{read_transcript, Paragraph, Transcript, TranscriptFormat, TranscriptSource};
+
+pub mod parse_captions;
+pub use parse_captions::{parse_srt, parse_webvtt};
+
+pub mod parse_json_transcripts;
+pub use parse_json_transcripts::{
+    parse_descript_json, parse_otter_json, parse_riverside_json, parse_whisper_json,
+};
+
+pub mod mark_transcript;
+pub use mark_transcript::{mark_transcript, Placement};
+
+pub mod placement_report;
+pub use placement_report::{
+    check_placements, format_placement_report, PlacementWarning, ReportFormat,
+};
+
+pub mod shift_transcript;
+pub use shift_transcript::shift_transcript;
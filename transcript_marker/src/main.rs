@@ -1,16 +1,171 @@
-use clap::{arg, command, value_parser};
+use clap::{arg, command, value_parser, ArgAction, ArgMatches, Command};
 use std::path::PathBuf;
+use std::time::Duration;
 
-mod mark_transcript;
-use mark_transcript::mark_transcript;
+mod cli_error;
+use cli_error::CliError;
 
-use common::parse_outline;
+use transcript_marker::{
+    check_placements, format_placement_report, mark_transcript, read_transcript, shift_transcript,
+    ReportFormat, TranscriptFormat,
+};
+
+use common::cli::{
+    audio_path_arg, duration_arg, fcpxml_markers_arg, frame_rate_arg, get_episode_duration,
+    get_outline_options, outline_format_arg,
+};
+use common::{
+    check_outline, format_outline, format_outline_with_style, read_file, read_outline,
+    read_outline_with_format, shift_outline, tidy_outline, write_file, ChapterRules, EditOperation,
+    OutlineFormat, SlugStyle, TimeCodeStyle,
+};
 
 fn main() {
-    let matches = command!()
+    let matches = cmd().get_matches();
+
+    let result = match matches.subcommand() {
+        Some(("shift", sub_matches)) => shift(sub_matches),
+        Some(("outline", sub_matches)) => match sub_matches.subcommand() {
+            Some(("fmt", fmt_matches)) => format_outline_file(fmt_matches),
+            _ => unreachable!("Outline subcommand should be provided"),
+        },
+        _ => mark(&matches),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e.to_string().trim_end());
+        std::process::exit(e.exit_code());
+    }
+}
+
+fn mark(matches: &ArgMatches) -> Result<(), CliError> {
+    let transcript_path: &PathBuf = matches
+        .get_one("transcript_path")
+        .expect("A transcript file was provided");
+    let outline_path: &PathBuf = matches
+        .get_one("outline_path")
+        .expect("A time codes file was provided");
+    let out_file_path: &PathBuf = matches
+        .get_one("out_file_path")
+        .expect("An output file was provided");
+    let outline_options = get_outline_options(matches);
+    let chapter_rules: &ChapterRules = matches
+        .get_one("chapter_rules")
+        .expect("Chapter rules were provided");
+    let strict = matches.get_flag("strict");
+    let episode_duration = get_episode_duration(matches, CliError::Audio)?;
+
+    let transcript_format = matches
+        .get_one::<TranscriptFormat>("transcript_format")
+        .copied();
+    let slug_style: &SlugStyle = matches
+        .get_one("slug_style")
+        .expect("A slug style was provided");
+    let report_format = matches.get_one::<ReportFormat>("report").copied();
+    let max_drift: &u64 = matches
+        .get_one("max_drift")
+        .expect("A maximum drift was provided");
+    let max_drift = Duration::from_secs(*max_drift);
+
+    let transcript = read_file(transcript_path)?;
+    let transcript = read_transcript(transcript_path, &transcript, transcript_format)
+        .map_err(|e| CliError::Transcript(transcript_path.clone(), e))?;
+    let outline_entries = read_outline(outline_path, &outline_options)?;
+    for violation in check_outline(&outline_entries, chapter_rules, episode_duration, strict)? {
+        eprintln!("Warning: {}", violation);
+    }
+    let (new_transcript, placements) =
+        mark_transcript(&transcript, &outline_entries, episode_duration, *slug_style)
+            .map_err(|e| CliError::Transcript(transcript_path.clone(), e))?;
+    let warnings = check_placements(&transcript, &placements, max_drift);
+    for warning in &warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    if let Some(report_format) = report_format {
+        print!(
+            "{}",
+            format_placement_report(&placements, &warnings, max_drift, report_format)
+        );
+    }
+
+    write_file(out_file_path, new_transcript)?;
+    Ok(())
+}
+
+fn shift(matches: &ArgMatches) -> Result<(), CliError> {
+    let transcript_path: &PathBuf = matches
+        .get_one("transcript_path")
+        .expect("A transcript file was provided");
+    let outline_path: &PathBuf = matches
+        .get_one("outline_path")
+        .expect("A time codes file was provided");
+    let out_transcript_path: &PathBuf = matches
+        .get_one("out_transcript_path")
+        .expect("An output transcript file was provided");
+    let out_outline_path: &PathBuf = matches
+        .get_one("out_outline_path")
+        .expect("An output outline file was provided");
+    let outline_options = get_outline_options(matches);
+    let edits = matches
+        .get_many::<EditOperation>("edit")
+        .expect("At least one edit was provided")
+        .copied()
+        .collect::<Vec<EditOperation>>();
+
+    let transcript = read_file(transcript_path)?;
+    let outline_entries = read_outline(outline_path, &outline_options)?;
+    let (outline_entries, warnings) = shift_outline(&outline_entries, &edits);
+    for warning in warnings {
+        eprintln!("Warning: {}", warning);
+    }
+    let new_transcript = shift_transcript(&transcript, &edits)
+        .map_err(|e| CliError::Transcript(transcript_path.clone(), e))?;
+
+    write_file(out_transcript_path, new_transcript)?;
+    write_file(out_outline_path, format_outline(&outline_entries))?;
+    Ok(())
+}
+
+fn format_outline_file(matches: &ArgMatches) -> Result<(), CliError> {
+    let outline_path: &PathBuf = matches
+        .get_one("outline_path")
+        .expect("An outline file was provided");
+    let time_code_style: &TimeCodeStyle = matches
+        .get_one("time_code_style")
+        .expect("A time code style was provided");
+    let check = matches.get_flag("check");
+    let outline_options = get_outline_options(matches);
+
+    let (outline_entries, format) = read_outline_with_format(outline_path, &outline_options)?;
+    let out_file_path = match matches.get_one::<PathBuf>("out_file_path") {
+        Some(out_file_path) => out_file_path,
+        None if format != OutlineFormat::Text && !check => {
+            return Err(CliError::NotTextOutline {
+                path: outline_path.clone(),
+                format,
+            })
+        }
+        None => outline_path,
+    };
+    let formatted = format_outline_with_style(&tidy_outline(&outline_entries), *time_code_style);
+
+    if check {
+        let current = read_file(out_file_path)?;
+        if current != formatted {
+            return Err(CliError::NotFormatted(out_file_path.clone()));
+        }
+        return Ok(());
+    }
+    write_file(out_file_path, formatted)?;
+    Ok(())
+}
+
+fn cmd() -> Command {
+    command!()
         .propagate_version(true)
         .author("Audrow Nash")
         .about("Marks a transcript with time codes and adds a table of contents")
+        .args_conflicts_with_subcommands(true)
+        .subcommand_negates_reqs(true)
         .arg(
             arg!(
                 <transcript_path> "Sets the path to the transcript file"
@@ -19,7 +174,7 @@ fn main() {
         )
         .arg(
             arg!(
-                <outline_path> "Sets the path to the outline file"
+                <outline_path> "Sets the path to the outline file or marker export"
             )
             .required(true)
             .value_parser(value_parser!(PathBuf)),
@@ -31,25 +186,166 @@ fn main() {
             .default_value("marked_transcript.md")
             .value_parser(value_parser!(PathBuf)),
         )
-        .get_matches();
+        .arg(
+            arg!(
+                --transcript_format <format> "Format of the transcript, detected from the file if not given: descript, srt, vtt, whisper, descript_json, otter or riverside"
+            )
+            .value_parser(value_parser!(TranscriptFormat)),
+        )
+        .arg(outline_format_arg())
+        .arg(frame_rate_arg())
+        .arg(fcpxml_markers_arg())
+        .arg(
+            arg!(
+                --chapter_rules <rules> "Chapter rules to check the outline against: youtube, spotify or podcasting2.0"
+            )
+            .default_value("youtube")
+            .value_parser(value_parser!(ChapterRules)),
+        )
+        .arg(
+            arg!(
+                --strict "Fail instead of warning when the outline breaks the chapter rules"
+            ),
+        )
+        .arg(duration_arg(
+            "Length of the episode, to show how long each chapter lasts",
+        ))
+        .arg(audio_path_arg())
+        .arg(
+            arg!(
+                --slug_style <style> "How the site showing the transcript makes heading links: github, pandoc or kramdown"
+            )
+            .default_value("github")
+            .value_parser(value_parser!(SlugStyle)),
+        )
+        .arg(
+            arg!(
+                --report [format] "Print where each chapter's heading was put and how far it drifted, as text or json"
+            )
+            .num_args(0..=1)
+            .require_equals(true)
+            .default_missing_value("text")
+            .value_parser(value_parser!(ReportFormat)),
+        )
+        .arg(
+            arg!(
+                --max_drift <seconds> "Warn when a heading is put more than this many seconds from its chapter's time"
+            )
+            .default_value("30")
+            .value_parser(value_parser!(u64)),
+        )
+        .subcommand(
+            Command::new("shift")
+                .about("Offsets the outline and transcript time codes after an edit")
+                .arg(
+                    arg!(
+                        <transcript_path> "Sets the path to the transcript file"
+                    )
+                    .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(
+                        <outline_path> "Sets the path to the outline file or marker export"
+                    )
+                    .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(
+                        -e --edit <edit> "An edit, like \"insert 0:45 at 12:30\" or \"cut 3:10-3:55\", using time codes from before any edits"
+                    )
+                    .required(true)
+                    .action(ArgAction::Append)
+                    .value_parser(value_parser!(EditOperation)),
+                )
+                .arg(
+                    arg!(
+                        --out_transcript_path <file> "Path for where to save the shifted transcript"
+                    )
+                    .default_value("shifted_transcript.md")
+                    .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    arg!(
+                        --out_outline_path <file> "Path for where to save the shifted outline"
+                    )
+                    .default_value("shifted_outline.txt")
+                    .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(outline_format_arg())
+                .arg(frame_rate_arg())
+                .arg(fcpxml_markers_arg()),
+        )
+        .subcommand(
+            Command::new("outline")
+                .about("Works with outline files")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("fmt")
+                        .about("Sorts, de-duplicates and rewrites an outline in a consistent style")
+                        .arg(
+                            arg!(
+                                <outline_path> "Path to the outline file or marker export"
+                            )
+                            .value_parser(value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            arg!(
+                                -o --out_file_path <file> "Path for where to save the formatted outline, the outline itself if not given"
+                            )
+                            .value_parser(value_parser!(PathBuf)),
+                        )
+                        .arg(
+                            arg!(
+                                --time_code_style <style> "How to write time codes: short (M:SS), hours (H:MM:SS) or minutes (MM:SS)"
+                            )
+                            .default_value("short")
+                            .value_parser(value_parser!(TimeCodeStyle)),
+                        )
+                        .arg(
+                            arg!(
+                                --check "Fail if the outline isn't already formatted instead of rewriting it"
+                            ),
+                        )
+                        .arg(outline_format_arg())
+                        .arg(frame_rate_arg())
+                        .arg(fcpxml_markers_arg()),
+                ),
+        )
+}
 
-    let transcript_path: &PathBuf = matches
-        .get_one("transcript_path")
-        .expect("A transcript file was provided");
-    let outline_path: &PathBuf = matches
-        .get_one("outline_path")
-        .expect("A time codes file was provided");
-    let out_file_path: &PathBuf = matches
-        .get_one("out_file_path")
-        .expect("An output file was provided");
+#[test]
+fn verify_cmd() {
+    cmd().debug_assert();
+}
 
-    let transcript =
-        std::fs::read_to_string(transcript_path).expect("The transcript file to be read");
-    let outline = std::fs::read_to_string(outline_path).expect("The time codes file to be read");
+#[test]
+fn leaves_utf_16_marker_exports_untouched() {
+    let path = std::env::temp_dir().join(format!("utf_16_markers_{}.csv", std::process::id()));
+    let text = "Marker Name\tDescription\tIn\nIntroduction\t\t00:00:00:00\n";
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+    std::fs::write(&path, &bytes).unwrap();
 
-    let mut outline_entries = parse_outline(&outline).expect("The time codes file to be parsed");
-    let new_transcript =
-        mark_transcript(&transcript, &mut outline_entries).expect("The transcript to be marked");
+    let matches = cmd().get_matches_from([
+        "transcript_marker",
+        "outline",
+        "fmt",
+        path.to_str().unwrap(),
+    ]);
+    let fmt_matches = matches
+        .subcommand_matches("outline")
+        .and_then(|matches| matches.subcommand_matches("fmt"))
+        .expect("The fmt subcommand was given");
+    let result = format_outline_file(fmt_matches);
+    let after = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
 
-    std::fs::write(out_file_path, new_transcript).expect("The output file to be written");
+    assert!(matches!(
+        result,
+        Err(CliError::NotTextOutline {
+            format: OutlineFormat::PremiereCsv,
+            ..
+        })
+    ));
+    assert_eq!(after, bytes);
 }
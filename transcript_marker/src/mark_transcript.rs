@@ -1,87 +1,382 @@
-use common::{OutlineEntry, TimeCode};
-use regex::Regex;
-use std::error::Error;
+use common::{Chapter, OutlineEntry, SlugStyle, Slugger, TimeCode};
+use std::time::Duration;
 
-pub fn mark_transcript(
-    text: &str,
-    outline_entries: &mut Vec<OutlineEntry>,
-) -> Result<String, Box<dyn Error>> {
-    let line_regex = Regex::new(r"\[(\d{2,}:\d{2}:\d{2})\] .*").expect("Line regex is valid");
+use crate::error::TranscriptError;
+use crate::transcript::{Paragraph, Transcript};
+
+/// Where an outline entry's heading was put in the marked transcript.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Placement {
+    pub entry: OutlineEntry,
+    /// The timestamp of the paragraph the heading is before, or `None` when
+    /// the entry starts after the transcript and its heading was added at
+    /// the end.
+    pub paragraph_start: Option<TimeCode>,
+}
 
+impl Placement {
+    /// How many seconds after the entry's time its heading was put, which is
+    /// negative when the heading is early.
+    pub fn drift(&self) -> Option<f64> {
+        self.paragraph_start
+            .map(|start| start.to_seconds() - self.entry.time_code.to_seconds())
+    }
+}
+
+/// Writes the transcript as Markdown, with a heading where each outline
+/// entry starts and a table of contents at the top. Every entry that is due
+/// by a paragraph gets its heading there, in order, and entries after the
+/// last paragraph are added at the end. Returns the marked transcript and
+/// where each entry's heading was put.
+///
+/// An entry that starts partway through a paragraph gets its heading at the
+/// sentence closest to its time, which splits the paragraph and gives the
+/// rest of it a timestamp. Sentences are timed from the transcript's word
+/// timings or, without them, by spreading the paragraph's words evenly until
+/// the next timestamp.
+///
+/// When `episode_duration` is given, the table of contents shows how long
+/// each chapter lasts. The table of contents links to headings the way
+/// `slug_style` makes their anchors.
+pub fn mark_transcript(
+    transcript: &Transcript,
+    outline_entries: &[OutlineEntry],
+    episode_duration: Option<Duration>,
+    slug_style: SlugStyle,
+) -> Result<(String, Vec<Placement>), TranscriptError> {
+    let mut outline_entries = outline_entries.to_vec();
     outline_entries.sort();
+    let chapters = match episode_duration {
+        Some(episode_duration) => Some(Chapter::from_outline(&outline_entries, episode_duration)?),
+        None => None,
+    };
 
     let mut output_text: Vec<String> = vec![];
-    output_text.push("## Table of Contents\n".into());
-    output_text.push(get_md_table_of_contents(&outline_entries) + "\n");
-    for line in text.lines() {
-        if let Some(captures) = line_regex.captures(line) {
-            let time_code = TimeCode::from_str(&captures[1])?;
-            if outline_entries.len() > 0 && time_code >= outline_entries[0].time_code {
-                let entry = outline_entries.remove(0);
-                output_text.push(format!("## {}\n", entry.text));
+    output_text.push(format!("## {}\n", TABLE_OF_CONTENTS));
+    output_text
+        .push(get_md_table_of_contents(&outline_entries, chapters.as_deref(), slug_style) + "\n");
+
+    let episode_end = episode_duration.and_then(TimeCode::from_duration);
+    let blocks = get_blocks(&transcript.paragraphs);
+    let mut marked_paragraphs: Vec<MarkedParagraph> = vec![];
+    for (index, block) in blocks.iter().enumerate() {
+        let next_start = blocks.get(index + 1).and_then(|block| block[0].start);
+        mark_block(
+            block,
+            next_start,
+            episode_end,
+            &mut outline_entries,
+            &mut marked_paragraphs,
+        );
+    }
+    let mut placements: Vec<Placement> = vec![];
+    for (index, marked_paragraph) in marked_paragraphs.into_iter().enumerate() {
+        if index > 0 {
+            output_text.push(String::new());
+        }
+        for entry in marked_paragraph.headings {
+            output_text.push(get_md_heading(&entry));
+            placements.push(Placement {
+                entry,
+                paragraph_start: marked_paragraph.paragraph.start,
+            });
+        }
+        output_text.push(marked_paragraph.paragraph.to_markdown());
+    }
+
+    for entry in outline_entries {
+        output_text.push(String::new());
+        output_text.push(get_md_heading(&entry));
+        placements.push(Placement {
+            entry,
+            paragraph_start: None,
+        });
+    }
+
+    Ok((output_text.join("\n"), placements))
+}
+
+/// A paragraph of the marked transcript and the headings that go before it.
+struct MarkedParagraph {
+    headings: Vec<OutlineEntry>,
+    paragraph: Paragraph,
+}
+
+/// Where a block of paragraphs can be split: before word `word` of its
+/// paragraph `paragraph`, said at `time`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct SplitPoint {
+    paragraph: usize,
+    word: usize,
+    time: TimeCode,
+}
+
+/// Groups paragraphs into blocks that each start with a timestamped
+/// paragraph, followed by the paragraphs without a timestamp that continue
+/// it, like a long Descript paragraph.
+fn get_blocks(paragraphs: &[Paragraph]) -> Vec<&[Paragraph]> {
+    let mut blocks = vec![];
+    let mut block_start = 0;
+    for (index, paragraph) in paragraphs.iter().enumerate() {
+        if index > block_start && paragraph.start.is_some() {
+            blocks.push(&paragraphs[block_start..index]);
+            block_start = index;
+        }
+    }
+    if block_start < paragraphs.len() {
+        blocks.push(&paragraphs[block_start..]);
+    }
+    blocks
+}
+
+/// Adds a block's paragraphs to `marked_paragraphs`, with the headings of
+/// the outline entries that are due by its start or that start inside it,
+/// removing them from `outline_entries`.
+fn mark_block(
+    block: &[Paragraph],
+    next_start: Option<TimeCode>,
+    episode_end: Option<TimeCode>,
+    outline_entries: &mut Vec<OutlineEntry>,
+    marked_paragraphs: &mut Vec<MarkedParagraph>,
+) {
+    let mut headings = vec![];
+    if let Some(start) = block[0].start {
+        while outline_entries
+            .first()
+            .is_some_and(|entry| start >= entry.time_code)
+        {
+            headings.push(outline_entries.remove(0));
+        }
+    }
+
+    let mut splits: Vec<(SplitPoint, OutlineEntry)> = vec![];
+    if let Some(split_points) = get_split_points(block, next_start, episode_end) {
+        let block_end = split_points[split_points.len() - 1];
+        while outline_entries
+            .first()
+            .is_some_and(|entry| entry.time_code < block_end.time)
+        {
+            let time_code = outline_entries[0].time_code;
+            let closest = split_points
+                .iter()
+                .min_by_key(|split_point| distance(split_point.time, time_code))
+                .expect("There is a split point at the end of the block");
+            if *closest == block_end {
+                break;
             }
+            splits.push((*closest, outline_entries.remove(0)));
         }
-        output_text.push(format!("{}", line.trim()));
     }
 
-    Ok(output_text.join("\n"))
+    for (paragraph_index, paragraph) in block.iter().enumerate() {
+        let words = paragraph.text.split_whitespace().collect::<Vec<&str>>();
+        let speaker = paragraph.speaker.clone().or(block[0].speaker.clone());
+        let mut timed_words = paragraph.words.as_slice();
+        let mut piece_start = 0;
+        let mut piece_time = paragraph.start;
+        for (split_point, entry) in splits
+            .iter()
+            .filter(|(split_point, _)| split_point.paragraph == paragraph_index)
+        {
+            if split_point.word > piece_start {
+                let piece_end = timed_words.partition_point(|word| word.start < split_point.time);
+                marked_paragraphs.push(MarkedParagraph {
+                    headings: std::mem::take(&mut headings),
+                    paragraph: Paragraph {
+                        start: piece_time,
+                        speaker: speaker.clone(),
+                        text: words[piece_start..split_point.word].join(" "),
+                        words: timed_words[..piece_end].to_vec(),
+                    },
+                });
+                timed_words = &timed_words[piece_end..];
+                piece_start = split_point.word;
+                piece_time = Some(split_point.time);
+            }
+            piece_time = piece_time.or(Some(split_point.time));
+            headings.push(entry.clone());
+        }
+
+        let paragraph = if piece_start == 0 && piece_time == paragraph.start {
+            paragraph.clone()
+        } else {
+            Paragraph {
+                start: piece_time,
+                speaker,
+                text: words[piece_start..].join(" "),
+                words: timed_words.to_vec(),
+            }
+        };
+        marked_paragraphs.push(MarkedParagraph {
+            headings: std::mem::take(&mut headings),
+            paragraph,
+        });
+    }
 }
 
-fn get_md_table_of_contents(outline_entries: &Vec<OutlineEntry>) -> String {
+/// The places inside a block it can be split at, which are the starts of its
+/// later paragraphs and sentences, followed by the end of the block. Words are
+/// timed from the block's word timings or, without them, spread evenly
+/// until the next block's start or the end of the episode. `None` when the
+/// block has no start or its end isn't known.
+fn get_split_points(
+    block: &[Paragraph],
+    next_start: Option<TimeCode>,
+    episode_end: Option<TimeCode>,
+) -> Option<Vec<SplitPoint>> {
+    let start = block[0].start?;
+    let words = block
+        .iter()
+        .map(|paragraph| paragraph.text.split_whitespace().collect::<Vec<&str>>())
+        .collect::<Vec<Vec<&str>>>();
+    let word_count = words.iter().map(Vec::len).sum::<usize>();
+    if word_count == 0 {
+        return None;
+    }
+
+    let has_word_timings = block
+        .iter()
+        .zip(&words)
+        .all(|(paragraph, words)| paragraph.words.len() == words.len());
+    let (word_starts, end) = if has_word_timings {
+        let end = next_start.or_else(|| Some(block.last()?.words.last()?.end))?;
+        let word_starts = block
+            .iter()
+            .flat_map(|paragraph| paragraph.words.iter().map(|word| word.start))
+            .collect::<Vec<TimeCode>>();
+        (word_starts, end)
+    } else {
+        let end = next_start.or(episode_end)?;
+        let length = end.saturating_duration_since(&start);
+        let word_starts = (0..word_count)
+            .map(|index| start + length.mul_f64(index as f64 / word_count as f64))
+            .collect::<Vec<TimeCode>>();
+        (word_starts, end)
+    };
+
+    let mut split_points = vec![];
+    let mut word_starts = word_starts.into_iter();
+    for (paragraph_index, words) in words.iter().enumerate() {
+        for (word_index, time) in word_starts.by_ref().take(words.len()).enumerate() {
+            let starts_block = paragraph_index == 0 && word_index == 0;
+            if !starts_block && (word_index == 0 || ends_sentence(words[word_index - 1])) {
+                split_points.push(SplitPoint {
+                    paragraph: paragraph_index,
+                    word: word_index,
+                    time,
+                });
+            }
+        }
+    }
+    split_points.push(SplitPoint {
+        paragraph: block.len(),
+        word: 0,
+        time: end,
+    });
+    Some(split_points)
+}
+
+fn ends_sentence(word: &str) -> bool {
+    word.trim_end_matches(['"', '\'', ')', '”', '’'])
+        .ends_with(['.', '?', '!'])
+}
+
+fn distance(a: TimeCode, b: TimeCode) -> Duration {
+    a.saturating_duration_since(&b)
+        .max(b.saturating_duration_since(&a))
+}
+
+const TABLE_OF_CONTENTS: &str = "Table of Contents";
+
+/// Lists the outline as links to its headings, with each chapter's length
+/// when `chapters` are known. Hidden entries still get a heading in the
+/// transcript but are left out of the list.
+///
+/// Links count every heading before them, including the table of contents
+/// and hidden entries, so that repeated titles link to the right heading.
+fn get_md_table_of_contents(
+    outline_entries: &[OutlineEntry],
+    chapters: Option<&[Chapter]>,
+    slug_style: SlugStyle,
+) -> String {
+    let mut slugger = Slugger::new(slug_style);
+    slugger.slug(TABLE_OF_CONTENTS);
     let mut output_text: Vec<String> = vec![];
-    for entry in outline_entries {
+    for (index, entry) in outline_entries.iter().enumerate() {
+        let slug = slugger.slug(&entry.text);
+        if entry.hidden {
+            continue;
+        }
+        let length = chapters
+            .and_then(|chapters| chapters.get(index))
+            .map(|chapter| format!(" ({})", chapter.length_label()))
+            .unwrap_or_default();
         output_text.push(format!(
-            "- [[{}] {}]({})",
+            "{}- [[{}] {}](#{}){}",
+            "  ".repeat(entry.level),
             entry.time_code,
             entry.text,
-            get_md_heading_url(&entry.text)
+            slug,
+            length
         ));
     }
     output_text.join("\n")
 }
 
-fn get_md_heading_url(text: &str) -> String {
-    format!(
-        "#{}",
-        text.to_lowercase()
-            .replace(" ", "-")
-            .chars()
-            .filter(|c| c.is_alphanumeric() || *c == '-')
-            .collect::<String>()
-    )
+fn get_md_heading(entry: &OutlineEntry) -> String {
+    format!("{} {}\n", get_md_heading_prefix(entry), entry.text)
+}
+
+/// Top level entries are `##` headings, and each level of nesting adds a `#`,
+/// up to the six levels Markdown supports.
+fn get_md_heading_prefix(entry: &OutlineEntry) -> String {
+    "#".repeat((entry.level + 2).min(6))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::transcript::{DescriptTranscript, TranscriptSource, Word};
     use insta::assert_snapshot;
 
+    fn descript(text: &str) -> Transcript {
+        DescriptTranscript
+            .parse(text)
+            .expect("The Descript transcript should parse")
+    }
+
     #[test]
     fn put_text_before_timecodes() {
-        let mut outline_entries: Vec<OutlineEntry> = vec![
+        let outline_entries: Vec<OutlineEntry> = vec![
             OutlineEntry {
                 time_code: TimeCode {
                     hours: 0,
                     minutes: 0,
                     seconds: 0,
+                    ..Default::default()
                 },
                 text: "Introduction".into(),
+                ..Default::default()
             },
             OutlineEntry {
                 time_code: TimeCode {
                     hours: 0,
                     minutes: 1,
                     seconds: 30,
+                    ..Default::default()
                 },
                 text: "Nag and Mike introduce themselves".into(),
+                ..Default::default()
             },
             OutlineEntry {
                 time_code: TimeCode {
                     hours: 0,
                     minutes: 3,
-                    seconds: 09,
+                    seconds: 9,
+                    ..Default::default()
                 },
                 text: "Nag and Mike introduce electric sheep".into(),
+                ..Default::default()
             },
         ];
 
@@ -97,21 +392,294 @@ And then over time, we plan to use those, automated robots to improve our own ma
 
 [00:03:09] **Michael Laskey:** Yeah, I can take that one."#;
 
-        let new_transcript = mark_transcript(transcript, &mut outline_entries)
-            .expect("Mark transcript should succeed");
+        let (new_transcript, _) = mark_transcript(
+            &descript(transcript),
+            &outline_entries,
+            None,
+            SlugStyle::GitHub,
+        )
+        .expect("Mark transcript should succeed");
+        assert_snapshot!(new_transcript);
+    }
+
+    #[test]
+    fn nests_headings_and_table_of_contents() {
+        let outline_entries: Vec<OutlineEntry> = vec![
+            OutlineEntry {
+                time_code: TimeCode::new(0, 0, 0).unwrap(),
+                text: "Introduction".into(),
+                level: 0,
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 1, 0).unwrap(),
+                text: "Background".into(),
+                level: 1,
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 2, 0).unwrap(),
+                text: "First robot".into(),
+                level: 2,
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 3, 0).unwrap(),
+                text: "Company".into(),
+                level: 0,
+                ..Default::default()
+            },
+        ];
+
+        let transcript = r#"[00:00:00] **Audrow Nash:** Welcome.
+
+[00:01:00] **Guest:** I studied robotics.
+
+[00:02:00] **Guest:** Then I built a robot.
+
+[00:03:00] **Guest:** So we started a company."#;
+
+        let (new_transcript, _) = mark_transcript(
+            &descript(transcript),
+            &outline_entries,
+            None,
+            SlugStyle::GitHub,
+        )
+        .expect("Mark transcript should succeed");
         assert_snapshot!(new_transcript);
     }
 
     #[test]
-    fn get_md_heading_url_test() {
-        assert_eq!(get_md_heading_url("Start"), "#start".to_string());
+    fn puts_back_to_back_chapters_before_the_same_paragraph() {
+        let outline_entries: Vec<OutlineEntry> = vec![
+            OutlineEntry {
+                time_code: TimeCode::new(0, 0, 0).unwrap(),
+                text: "Introduction".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 0, 20).unwrap(),
+                text: "Sponsor".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 0, 40).unwrap(),
+                text: "Background".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 2, 0).unwrap(),
+                text: "Company".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 5, 0).unwrap(),
+                text: "Wrap up".into(),
+                ..Default::default()
+            },
+        ];
+
+        let transcript = r#"[00:00:00] **Audrow Nash:** Welcome.
+
+[00:01:00] **Guest:** I studied robotics.
+
+[00:02:00] **Guest:** So we started a company."#;
+
+        let (new_transcript, placements) = mark_transcript(
+            &descript(transcript),
+            &outline_entries,
+            None,
+            SlugStyle::GitHub,
+        )
+        .expect("Mark transcript should succeed");
+        assert_snapshot!(new_transcript);
+        assert_eq!(
+            placements
+                .iter()
+                .map(|placement| placement.drift())
+                .collect::<Vec<Option<f64>>>(),
+            vec![Some(0.0), Some(40.0), Some(20.0), Some(0.0), None]
+        );
+        assert_eq!(outline_entries.len(), 5);
+    }
+
+    #[test]
+    fn splits_long_paragraphs_at_the_closest_sentence() {
+        let outline_entries: Vec<OutlineEntry> = vec![
+            OutlineEntry {
+                time_code: TimeCode::new(0, 12, 5).unwrap(),
+                text: "Background".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 12, 40).unwrap(),
+                text: "First robot".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 13, 30).unwrap(),
+                text: "Company".into(),
+                ..Default::default()
+            },
+        ];
+
+        let transcript = r#"[00:12:05] **Guest:** I studied robotics for a few years at university. Then I built my first robot in a garage.
+
+We started a company with two friends from the lab.
+
+[00:14:05] **Audrow Nash:** How did that go?"#;
+
+        let (new_transcript, placements) = mark_transcript(
+            &descript(transcript),
+            &outline_entries,
+            None,
+            SlugStyle::GitHub,
+        )
+        .expect("Mark transcript should succeed");
+        assert_snapshot!(new_transcript);
+        assert_eq!(
+            placements
+                .iter()
+                .map(|placement| placement.paragraph_start.map(|start| start.to_string()))
+                .collect::<Vec<Option<String>>>(),
+            vec![
+                Some("12:05".to_string()),
+                Some("12:43.571".to_string()),
+                Some("13:22.142".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_paragraphs_at_word_timings() {
+        let word = |text: &str, start: u32| Word {
+            start: TimeCode::new(0, 0, start).unwrap(),
+            end: TimeCode::new(0, 0, start + 1).unwrap(),
+            text: text.to_string(),
+        };
+        let transcript = Transcript {
+            paragraphs: vec![Paragraph {
+                start: Some(TimeCode::new(0, 0, 0).unwrap()),
+                speaker: Some("Rae".to_string()),
+                text: "Hi there. Let's talk robots.".to_string(),
+                words: vec![
+                    word("Hi", 0),
+                    word("there.", 1),
+                    word("Let's", 20),
+                    word("talk", 21),
+                    word("robots.", 22),
+                ],
+            }],
+        };
+        let outline_entries = vec![
+            OutlineEntry {
+                text: "Introduction".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 0, 15).unwrap(),
+                text: "Robots".into(),
+                ..Default::default()
+            },
+        ];
+
+        let (new_transcript, placements) =
+            mark_transcript(&transcript, &outline_entries, None, SlugStyle::GitHub)
+                .expect("Mark transcript should succeed");
+
+        assert!(new_transcript.ends_with(
+            "## Introduction\n\n[00:00:00] **Rae:** Hi there.\n\n## Robots\n\n[00:00:20] **Rae:** Let's talk robots."
+        ));
+        assert_eq!(placements[1].drift(), Some(5.0));
+    }
+
+    #[test]
+    fn leaves_hidden_entries_out_of_table_of_contents() {
+        let outline_entries = vec![
+            OutlineEntry {
+                text: "Introduction".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 1, 0).unwrap(),
+                text: "Sponsor".into(),
+                hidden: true,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            get_md_table_of_contents(&outline_entries, None, SlugStyle::GitHub),
+            "- [[0:00] Introduction](#introduction)"
+        );
+    }
+
+    #[test]
+    fn shows_chapter_lengths_in_table_of_contents() {
+        let outline_entries = vec![
+            OutlineEntry {
+                text: "Introduction".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 12, 0).unwrap(),
+                text: "Company".into(),
+                ..Default::default()
+            },
+        ];
+
+        let (new_transcript, _) = mark_transcript(
+            &descript("[00:00:00] Welcome."),
+            &outline_entries,
+            Some(Duration::from_secs(12 * 60 + 45)),
+            SlugStyle::GitHub,
+        )
+        .expect("Mark transcript should succeed");
+
+        assert!(new_transcript.starts_with(
+            "## Table of Contents\n\n- [[0:00] Introduction](#introduction) (12 min)\n- [[12:00] Company](#company) (45 sec)\n"
+        ));
+        assert!(mark_transcript(
+            &Transcript::default(),
+            &outline_entries,
+            Some(Duration::from_secs(60)),
+            SlugStyle::GitHub
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn links_repeated_titles_to_their_own_headings() {
+        let outline_entries = vec![
+            OutlineEntry {
+                text: "Questions".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 1, 0).unwrap(),
+                text: "Questions".into(),
+                hidden: true,
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 2, 0).unwrap(),
+                text: "Questions".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 3, 0).unwrap(),
+                text: "Table of contents".into(),
+                ..Default::default()
+            },
+        ];
+
         assert_eq!(
-            get_md_heading_url("Introducing Bradley + Luxonis"),
-            "#introducing-bradley--luxonis".to_string()
+            get_md_table_of_contents(&outline_entries, None, SlugStyle::GitHub),
+            "- [[0:00] Questions](#questions)\n- [[2:00] Questions](#questions-2)\n- [[3:00] Table of contents](#table-of-contents-1)"
         );
         assert_eq!(
-            get_md_heading_url("Special characters!@#$%^&*()_+[]~><👍🤖"),
-            "#special-characters".to_string()
+            get_md_table_of_contents(&outline_entries[3..], None, SlugStyle::Kramdown),
+            "- [[3:00] Table of contents](#table-of-contents-1)"
         );
     }
 }
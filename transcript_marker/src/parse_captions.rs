@@ -0,0 +1,180 @@
+use common::TimeCode;
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::error::TranscriptError;
+use crate::transcript::{merge_segments, Segment, Transcript};
+
+/// Reads SubRip captions and merges their cues into paragraphs.
+pub fn parse_srt(contents: &str) -> Result<Transcript, TranscriptError> {
+    let cues = blocks(contents)
+        .filter_map(|(line_number, lines)| parse_cue(line_number, &lines).transpose())
+        .collect::<Result<Vec<Segment>, TranscriptError>>()?;
+    Ok(merge_segments(cues))
+}
+
+/// Reads WebVTT captions and merges their cues into paragraphs, taking
+/// speakers from `<v Speaker>` voice tags.
+pub fn parse_webvtt(contents: &str) -> Result<Transcript, TranscriptError> {
+    let cues = blocks(contents)
+        .filter(|(_, lines)| {
+            !["WEBVTT", "NOTE", "STYLE", "REGION"]
+                .iter()
+                .any(|keyword| lines[0].trim_start_matches('\u{feff}').starts_with(keyword))
+        })
+        .filter_map(|(line_number, lines)| parse_cue(line_number, &lines).transpose())
+        .collect::<Result<Vec<Segment>, TranscriptError>>()?;
+    Ok(merge_segments(cues))
+}
+
+/// Whether the first block of `contents` is a cue with a valid timing line,
+/// like `00:00:01,000 --> 00:00:02,500`, as SubRip captions start with.
+pub(crate) fn starts_with_cue(contents: &str) -> bool {
+    blocks(contents)
+        .next()
+        .is_some_and(|(line_number, lines)| matches!(parse_cue(line_number, &lines), Ok(Some(_))))
+}
+
+/// Splits captions into blocks separated by blank lines, with the line
+/// number each block starts on.
+fn blocks(contents: &str) -> impl Iterator<Item = (usize, Vec<&str>)> {
+    let mut blocks: Vec<(usize, Vec<&str>)> = vec![];
+    let mut in_block = false;
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            in_block = false;
+        } else if in_block {
+            blocks.last_mut().expect("A block was started").1.push(line);
+        } else {
+            blocks.push((index + 1, vec![line]));
+            in_block = true;
+        }
+    }
+    blocks.into_iter()
+}
+
+/// Reads a cue from its optional identifier, timing line and text, or
+/// `None` for a block without a timing line.
+fn parse_cue(line_number: usize, lines: &[&str]) -> Result<Option<Segment>, TranscriptError> {
+    let Some(timing_index) = lines.iter().position(|line| line.contains("-->")) else {
+        return Ok(None);
+    };
+    let line_number = line_number + timing_index;
+    let invalid_cue = |message: String| TranscriptError::InvalidCue {
+        line_number,
+        message,
+    };
+
+    let (start, rest) = lines[timing_index]
+        .split_once("-->")
+        .expect("The timing line has an arrow");
+    let end = rest.split_whitespace().next().unwrap_or_default();
+    let parse_time = |text: &str| {
+        text.trim()
+            .replace(',', ".")
+            .parse::<TimeCode>()
+            .map_err(|e| invalid_cue(format!("Invalid time '{}': {}", text.trim(), e)))
+    };
+
+    let text = lines[timing_index + 1..].join(" ");
+    let speaker = voice_regex()
+        .captures(&text)
+        .map(|captures| captures[1].trim().to_string());
+    Ok(Some(Segment {
+        start: parse_time(start)?,
+        end: parse_time(end)?,
+        speaker,
+        text: clean_cue_text(&text),
+        words: vec![],
+    }))
+}
+
+/// Matches a WebVTT voice tag, like `<v Rae>` or `<v.host Rae>`, capturing
+/// the speaker.
+fn voice_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"<v(?:\.[^ \t>]*)?[ \t]+([^>]+)>").expect("Voice regex is valid")
+    })
+}
+
+/// Matches styling tags and inline timestamps, like `<i>`, `<00:01.000>` or
+/// SubStation's `{\an8}`.
+fn tag_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"<[^>]*>|\{\\[^}]*\}").expect("Tag regex is valid"))
+}
+
+/// Removes styling tags and inline timestamps from a cue's text and decodes
+/// its character references.
+fn clean_cue_text(text: &str) -> String {
+    tag_regex()
+        .replace_all(text, "")
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::Paragraph;
+
+    #[test]
+    fn merges_srt_cues_into_paragraphs() {
+        let srt = "1\r\n00:00:00,000 --> 00:00:02,500\r\nWelcome to the show.\r\n\r\n2\r\n00:00:02,600 --> 00:00:05,000\r\n<i>Today</i> we talk\r\nabout robots.\r\n\r\n3\r\n00:00:09,000 --> 00:00:11,000\r\n{\\an8}After a pause &amp; more.\r\n";
+
+        let transcript = parse_srt(srt).expect("parse_srt should succeed");
+
+        assert_eq!(
+            transcript.paragraphs,
+            vec![
+                Paragraph {
+                    start: Some(TimeCode::new(0, 0, 0).unwrap()),
+                    speaker: None,
+                    text: "Welcome to the show. Today we talk about robots.".to_string(),
+                    words: vec![],
+                },
+                Paragraph {
+                    start: Some(TimeCode::new(0, 0, 9).unwrap()),
+                    speaker: None,
+                    text: "After a pause & more.".to_string(),
+                    words: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_webvtt_paragraphs_by_speaker() {
+        let vtt = "WEBVTT - Episode 1\n\nNOTE Exported from the editor\n\nintro\n00:01.000 --> 00:03.000 align:start\n<v Audrow Nash>Hi, <b>Rae</b>.\n\n00:03.500 --> 00:05.000\n<v.guest Rae>Hello!</v>\n\n00:05.200 --> 00:07.000\nThanks for <00:05.800>having me.\n";
+
+        let transcript = parse_webvtt(vtt).expect("parse_webvtt should succeed");
+
+        assert_eq!(
+            transcript
+                .paragraphs
+                .iter()
+                .map(Paragraph::to_markdown)
+                .collect::<Vec<String>>(),
+            vec![
+                "[00:00:01] **Audrow Nash:** Hi, Rae.",
+                "[00:00:03] **Rae:** Hello! Thanks for having me.",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_the_line_of_invalid_cues() {
+        assert_eq!(
+            parse_srt("1\n00:00:01,000 --> soon\nHi\n")
+                .unwrap_err()
+                .to_string(),
+            "Invalid caption on line 2: Invalid time 'soon': invalid digit found in string"
+        );
+    }
+}
@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+
+use common::TimeCode;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::TranscriptError;
+use crate::transcript::{merge_segments, Paragraph, Segment, Transcript, Word};
+
+/// A Whisper `verbose_json` transcript, from the OpenAI API, whisper.cpp or
+/// WhisperX. WhisperX adds speakers to segments and words.
+#[derive(Debug, Deserialize)]
+struct WhisperTranscript {
+    segments: Vec<WhisperSegment>,
+    /// Word timings for the whole transcript, which the OpenAI API returns
+    /// instead of words in each segment.
+    #[serde(default)]
+    words: Vec<WhisperWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperSegment {
+    start: f64,
+    end: f64,
+    text: String,
+    #[serde(default)]
+    speaker: Option<String>,
+    #[serde(default)]
+    words: Vec<WhisperWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhisperWord {
+    word: String,
+    start: f64,
+    end: f64,
+}
+
+/// A Descript JSON export, with paragraphs that name their speaker by id.
+#[derive(Debug, Deserialize)]
+struct DescriptJson {
+    #[serde(default)]
+    speakers: Vec<DescriptSpeaker>,
+    paragraphs: Vec<DescriptParagraph>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DescriptSpeaker {
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct DescriptParagraph {
+    start: f64,
+    #[serde(default)]
+    speaker: Option<String>,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    words: Vec<DescriptWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DescriptWord {
+    text: String,
+    start: f64,
+    end: f64,
+}
+
+/// An Otter JSON export, with speaker turns timed in milliseconds.
+#[derive(Debug, Deserialize)]
+struct OtterJson {
+    #[serde(default)]
+    speakers: Vec<OtterSpeaker>,
+    transcripts: Vec<OtterTurn>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OtterSpeaker {
+    id: u64,
+    speaker_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OtterTurn {
+    start_offset: u64,
+    #[serde(default)]
+    speaker_id: Option<u64>,
+    transcript: String,
+    #[serde(default)]
+    words: Vec<OtterWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OtterWord {
+    word: String,
+    start_offset: u64,
+    end_offset: u64,
+}
+
+/// A Riverside JSON export, a list of speaker turns.
+#[derive(Debug, Deserialize)]
+struct RiversideTurn {
+    speaker: String,
+    start: f64,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    words: Vec<RiversideWord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RiversideWord {
+    word: String,
+    start: f64,
+    end: f64,
+}
+
+/// Reads a Whisper `verbose_json` transcript, joining its segments into
+/// paragraphs.
+pub fn parse_whisper_json(contents: &str) -> Result<Transcript, TranscriptError> {
+    whisper_transcript(from_json(contents)?)
+}
+
+/// Reads a Whisper transcript that has already been parsed as JSON.
+pub(crate) fn read_whisper_json(json: Value) -> Result<Transcript, TranscriptError> {
+    whisper_transcript(from_value(json)?)
+}
+
+fn whisper_transcript(whisper: WhisperTranscript) -> Result<Transcript, TranscriptError> {
+    let mut unassigned_words = whisper.words.into_iter().peekable();
+    let segments = whisper
+        .segments
+        .into_iter()
+        .map(|segment| {
+            let mut words = segment
+                .words
+                .into_iter()
+                .map(|word| to_word(&word.word, word.start, word.end))
+                .collect::<Result<Vec<Word>, TranscriptError>>()?;
+            if words.is_empty() {
+                while let Some(word) = unassigned_words.next_if(|word| word.start < segment.end) {
+                    words.push(to_word(&word.word, word.start, word.end)?);
+                }
+            }
+            Ok(Segment {
+                start: seconds(segment.start)?,
+                end: seconds(segment.end)?,
+                speaker: segment.speaker,
+                text: segment.text.trim().to_string(),
+                words,
+            })
+        })
+        .collect::<Result<Vec<Segment>, TranscriptError>>()?;
+    Ok(merge_segments(segments))
+}
+
+/// Reads a Descript JSON export, keeping its paragraphs.
+pub fn parse_descript_json(contents: &str) -> Result<Transcript, TranscriptError> {
+    descript_transcript(from_json(contents)?)
+}
+
+/// Reads a Descript export that has already been parsed as JSON.
+pub(crate) fn read_descript_json(json: Value) -> Result<Transcript, TranscriptError> {
+    descript_transcript(from_value(json)?)
+}
+
+fn descript_transcript(descript: DescriptJson) -> Result<Transcript, TranscriptError> {
+    let speakers = descript
+        .speakers
+        .into_iter()
+        .map(|speaker| (speaker.id, speaker.name))
+        .collect::<HashMap<String, String>>();
+    let paragraphs = descript
+        .paragraphs
+        .into_iter()
+        .map(|paragraph| {
+            let words = paragraph
+                .words
+                .iter()
+                .map(|word| to_word(&word.text, word.start, word.end))
+                .collect::<Result<Vec<Word>, TranscriptError>>()?;
+            let speaker = paragraph
+                .speaker
+                .map(|speaker| speakers.get(&speaker).cloned().unwrap_or(speaker));
+            to_paragraph(paragraph.start, speaker, paragraph.text, words)
+        })
+        .collect::<Result<Vec<Paragraph>, TranscriptError>>()?;
+    Ok(Transcript { paragraphs })
+}
+
+/// Reads an Otter JSON export, with a paragraph for each speaker turn.
+pub fn parse_otter_json(contents: &str) -> Result<Transcript, TranscriptError> {
+    otter_transcript(from_json(contents)?)
+}
+
+/// Reads an Otter export that has already been parsed as JSON.
+pub(crate) fn read_otter_json(json: Value) -> Result<Transcript, TranscriptError> {
+    otter_transcript(from_value(json)?)
+}
+
+fn otter_transcript(otter: OtterJson) -> Result<Transcript, TranscriptError> {
+    let speakers = otter
+        .speakers
+        .into_iter()
+        .map(|speaker| (speaker.id, speaker.speaker_name))
+        .collect::<HashMap<u64, String>>();
+    let milliseconds = |offset: u64| offset as f64 / 1000.0;
+    let paragraphs = otter
+        .transcripts
+        .into_iter()
+        .map(|turn| {
+            let words = turn
+                .words
+                .iter()
+                .map(|word| {
+                    to_word(
+                        &word.word,
+                        milliseconds(word.start_offset),
+                        milliseconds(word.end_offset),
+                    )
+                })
+                .collect::<Result<Vec<Word>, TranscriptError>>()?;
+            let speaker = turn.speaker_id.and_then(|id| speakers.get(&id).cloned());
+            to_paragraph(
+                milliseconds(turn.start_offset),
+                speaker,
+                Some(turn.transcript),
+                words,
+            )
+        })
+        .collect::<Result<Vec<Paragraph>, TranscriptError>>()?;
+    Ok(Transcript { paragraphs })
+}
+
+/// Reads a Riverside JSON export, with a paragraph for each speaker turn.
+pub fn parse_riverside_json(contents: &str) -> Result<Transcript, TranscriptError> {
+    riverside_transcript(from_json(contents)?)
+}
+
+/// Reads a Riverside export that has already been parsed as JSON.
+pub(crate) fn read_riverside_json(json: Value) -> Result<Transcript, TranscriptError> {
+    riverside_transcript(from_value(json)?)
+}
+
+fn riverside_transcript(turns: Vec<RiversideTurn>) -> Result<Transcript, TranscriptError> {
+    let paragraphs = turns
+        .into_iter()
+        .map(|turn| {
+            let words = turn
+                .words
+                .iter()
+                .map(|word| to_word(&word.word, word.start, word.end))
+                .collect::<Result<Vec<Word>, TranscriptError>>()?;
+            to_paragraph(turn.start, Some(turn.speaker), turn.text, words)
+        })
+        .collect::<Result<Vec<Paragraph>, TranscriptError>>()?;
+    Ok(Transcript { paragraphs })
+}
+
+fn from_json<T: DeserializeOwned>(contents: &str) -> Result<T, TranscriptError> {
+    serde_json::from_str(contents.trim_start_matches('\u{feff}'))
+        .map_err(|e| TranscriptError::InvalidJson(e.to_string()))
+}
+
+fn from_value<T: DeserializeOwned>(json: Value) -> Result<T, TranscriptError> {
+    serde_json::from_value(json).map_err(|e| TranscriptError::InvalidJson(e.to_string()))
+}
+
+fn seconds(seconds: f64) -> Result<TimeCode, TranscriptError> {
+    TimeCode::from_seconds(seconds).map_err(|e| TranscriptError::InvalidJson(e.to_string()))
+}
+
+fn to_word(text: &str, start: f64, end: f64) -> Result<Word, TranscriptError> {
+    Ok(Word {
+        start: seconds(start)?,
+        end: seconds(end)?,
+        text: text.trim().to_string(),
+    })
+}
+
+/// Builds a paragraph, writing out its words if the export has no text for
+/// it.
+fn to_paragraph(
+    start: f64,
+    speaker: Option<String>,
+    text: Option<String>,
+    words: Vec<Word>,
+) -> Result<Paragraph, TranscriptError> {
+    let text = text.unwrap_or_else(|| {
+        words
+            .iter()
+            .map(|word| word.text.as_str())
+            .collect::<Vec<&str>>()
+            .join(" ")
+    });
+    Ok(Paragraph {
+        start: Some(seconds(start)?),
+        speaker,
+        text: text.split_whitespace().collect::<Vec<&str>>().join(" "),
+        words,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn markdown(transcript: &Transcript) -> Vec<String> {
+        transcript
+            .paragraphs
+            .iter()
+            .map(Paragraph::to_markdown)
+            .collect()
+    }
+
+    #[test]
+    fn joins_whisper_segments_into_paragraphs() {
+        let json = r#"{
+            "text": "Welcome to the show. Thanks for having me.",
+            "segments": [
+                {"id": 0, "start": 0.0, "end": 1.5, "text": " Welcome to", "speaker": "SPEAKER_00",
+                 "words": [{"word": " Welcome", "start": 0.0, "end": 0.6}, {"word": " to", "start": 0.7, "end": 1.5}]},
+                {"id": 1, "start": 1.6, "end": 2.4, "text": " the show.", "speaker": "SPEAKER_00",
+                 "words": [{"word": " the", "start": 1.6, "end": 1.9}, {"word": " show.", "start": 2.0, "end": 2.4}]},
+                {"id": 2, "start": 3.0, "end": 5.0, "text": " Thanks for having me.", "speaker": "SPEAKER_01"}
+            ]
+        }"#;
+
+        let transcript = parse_whisper_json(json).expect("parse_whisper_json should succeed");
+
+        assert_eq!(
+            markdown(&transcript),
+            vec![
+                "[00:00:00] **SPEAKER_00:** Welcome to the show.",
+                "[00:00:03] **SPEAKER_01:** Thanks for having me.",
+            ]
+        );
+        assert_eq!(
+            transcript.paragraphs[0].words[3],
+            Word {
+                start: TimeCode::from_seconds(2.0).unwrap(),
+                end: TimeCode::from_seconds(2.4).unwrap(),
+                text: "show.".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn assigns_top_level_whisper_words_to_segments() {
+        let json = r#"{
+            "segments": [
+                {"start": 0.0, "end": 1.0, "text": "Hi there."},
+                {"start": 1.2, "end": 2.0, "text": "Hello."}
+            ],
+            "words": [
+                {"word": "Hi", "start": 0.0, "end": 0.4},
+                {"word": "there.", "start": 0.5, "end": 1.0},
+                {"word": "Hello.", "start": 1.2, "end": 2.0}
+            ]
+        }"#;
+
+        let transcript = parse_whisper_json(json).expect("parse_whisper_json should succeed");
+
+        assert_eq!(
+            transcript.paragraphs[0]
+                .words
+                .iter()
+                .map(|word| word.text.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["Hi", "there.", "Hello."]
+        );
+    }
+
+    #[test]
+    fn names_descript_speakers() {
+        let json = r#"{
+            "speakers": [{"id": "s1", "name": "Audrow Nash"}],
+            "paragraphs": [
+                {"speaker": "s1", "start": 97.2, "end": 99.0,
+                 "words": [{"text": "Yeah.", "start": 97.2, "end": 97.6}, {"text": "Hi", "start": 97.8, "end": 99.0}]}
+            ]
+        }"#;
+
+        let transcript = parse_descript_json(json).expect("parse_descript_json should succeed");
+
+        assert_eq!(
+            markdown(&transcript),
+            vec!["[00:01:37] **Audrow Nash:** Yeah. Hi"]
+        );
+    }
+
+    #[test]
+    fn reads_otter_turns() {
+        let json = r#"{
+            "speakers": [{"id": 7, "speaker_name": "Rae"}],
+            "transcripts": [
+                {"start_offset": 61500, "end_offset": 64000, "speaker_id": 7, "transcript": "Let's talk robots.",
+                 "words": [{"word": "Let's", "start_offset": 61500, "end_offset": 61900}]},
+                {"start_offset": 65000, "end_offset": 66000, "transcript": "Sure."}
+            ]
+        }"#;
+
+        let transcript = parse_otter_json(json).expect("parse_otter_json should succeed");
+
+        assert_eq!(
+            markdown(&transcript),
+            vec!["[00:01:01] **Rae:** Let's talk robots.", "[00:01:05] Sure."]
+        );
+        assert_eq!(
+            transcript.paragraphs[0].words[0].start,
+            TimeCode::from_seconds(61.5).unwrap()
+        );
+    }
+
+    #[test]
+    fn reads_riverside_turns() {
+        let json = r#"[
+            {"speaker": "Audrow", "start": 0, "end": 2.5, "text": "Welcome  back.",
+             "words": [{"word": "Welcome", "start": 0, "end": 1}, {"word": "back.", "start": 1.1, "end": 2.5}]}
+        ]"#;
+
+        let transcript = parse_riverside_json(json).expect("parse_riverside_json should succeed");
+
+        assert_eq!(
+            markdown(&transcript),
+            vec!["[00:00:00] **Audrow:** Welcome back."]
+        );
+        assert_eq!(transcript.paragraphs[0].words.len(), 2);
+    }
+
+    #[test]
+    fn reports_invalid_json() {
+        assert_eq!(
+            parse_whisper_json("{\"segments\": [{\"start\": 0}]}")
+                .unwrap_err()
+                .to_string(),
+            "Invalid JSON transcript: missing field `end` at line 1 column 26"
+        );
+    }
+}
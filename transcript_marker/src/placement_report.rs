@@ -0,0 +1,388 @@
+use common::TimeCode;
+use serde::Serialize;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::mark_transcript::Placement;
+use crate::transcript::{format_transcript_time_code, Transcript};
+
+/// Something that may be wrong with where a heading was put. `index` is the
+/// position of the placement in the placements that were checked.
+#[derive(Debug, PartialEq, Clone)]
+pub enum PlacementWarning {
+    /// The heading is further from the entry's time than the allowed drift.
+    Drift {
+        index: usize,
+        placement: Placement,
+        max_drift: Duration,
+    },
+    /// The entry starts before the transcript's first timestamp.
+    BeforeTranscript {
+        index: usize,
+        placement: Placement,
+        first_timestamp: TimeCode,
+    },
+    /// The entry starts after the transcript's last timestamp, or the
+    /// transcript has no timestamps.
+    AfterTranscript {
+        index: usize,
+        placement: Placement,
+        last_timestamp: Option<TimeCode>,
+    },
+}
+
+impl PlacementWarning {
+    pub fn index(&self) -> usize {
+        match self {
+            PlacementWarning::Drift { index, .. }
+            | PlacementWarning::BeforeTranscript { index, .. }
+            | PlacementWarning::AfterTranscript { index, .. } => *index,
+        }
+    }
+
+    pub fn placement(&self) -> &Placement {
+        match self {
+            PlacementWarning::Drift { placement, .. }
+            | PlacementWarning::BeforeTranscript { placement, .. }
+            | PlacementWarning::AfterTranscript { placement, .. } => placement,
+        }
+    }
+
+    /// A short name for the warning, used in JSON reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlacementWarning::Drift { .. } => "drift",
+            PlacementWarning::BeforeTranscript { .. } => "before_transcript",
+            PlacementWarning::AfterTranscript { .. } => "after_transcript",
+        }
+    }
+}
+
+impl std::fmt::Display for PlacementWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entry = &self.placement().entry;
+        write!(f, "Chapter '{}' at {} ", entry.text, entry.time_code)?;
+        match self {
+            PlacementWarning::Drift {
+                placement,
+                max_drift,
+                ..
+            } => write!(
+                f,
+                "was placed {} seconds from its time, more than the {} allowed",
+                format_drift(placement.drift().unwrap_or_default()),
+                max_drift.as_secs_f64()
+            ),
+            PlacementWarning::BeforeTranscript {
+                first_timestamp, ..
+            } => write!(
+                f,
+                "starts before the first timestamp of the transcript, [{}]",
+                format_transcript_time_code(first_timestamp)
+            ),
+            PlacementWarning::AfterTranscript {
+                placement,
+                last_timestamp,
+                ..
+            } => {
+                match last_timestamp {
+                    Some(last_timestamp) => write!(
+                        f,
+                        "starts after the last timestamp of the transcript, [{}]",
+                        format_transcript_time_code(last_timestamp)
+                    )?,
+                    None => write!(f, "can't be placed in a transcript without timestamps")?,
+                }
+                if placement.paragraph_start.is_none() {
+                    write!(f, ", so it was added at the end")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Checks where each heading was put, warning about headings that drifted
+/// more than `max_drift` from their entry's time and entries that are
+/// outside of the transcript's timestamps.
+pub fn check_placements(
+    transcript: &Transcript,
+    placements: &[Placement],
+    max_drift: Duration,
+) -> Vec<PlacementWarning> {
+    let mut timestamps = transcript
+        .paragraphs
+        .iter()
+        .filter_map(|paragraph| paragraph.start);
+    let first_timestamp = timestamps.next();
+    let last_timestamp = timestamps.next_back().or(first_timestamp);
+
+    let mut warnings = vec![];
+    for (index, placement) in placements.iter().enumerate() {
+        let time_code = placement.entry.time_code;
+        if let Some(first_timestamp) = first_timestamp.filter(|first| time_code < *first) {
+            warnings.push(PlacementWarning::BeforeTranscript {
+                index,
+                placement: placement.clone(),
+                first_timestamp,
+            });
+        }
+        if placement.paragraph_start.is_none()
+            || last_timestamp.is_some_and(|last| time_code > last)
+        {
+            warnings.push(PlacementWarning::AfterTranscript {
+                index,
+                placement: placement.clone(),
+                last_timestamp,
+            });
+        }
+        if placement
+            .drift()
+            .is_some_and(|drift| drift.abs() > max_drift.as_secs_f64())
+        {
+            warnings.push(PlacementWarning::Drift {
+                index,
+                placement: placement.clone(),
+                max_drift,
+            });
+        }
+    }
+    warnings
+}
+
+/// How a placement report is written.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ReportFormat {
+    /// A line for each outline entry.
+    Text,
+    /// A JSON object for scripts.
+    Json,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<ReportFormat, Self::Err> {
+        match text.to_lowercase().as_str() {
+            "text" | "txt" => Ok(ReportFormat::Text),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(format!(
+                "Unknown report format '{}', expected text or json",
+                text
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ReportFormat::Text => "text",
+            ReportFormat::Json => "json",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Serialize)]
+struct JsonReport {
+    max_drift_seconds: f64,
+    entries: Vec<JsonReportEntry>,
+}
+
+#[derive(Serialize)]
+struct JsonReportEntry {
+    text: String,
+    time_code: String,
+    seconds: f64,
+    paragraph_start: Option<String>,
+    drift_seconds: Option<f64>,
+    warnings: Vec<String>,
+}
+
+/// Writes where each outline entry's heading was put and how far it drifted
+/// from the entry's time, with the warnings for it.
+pub fn format_placement_report(
+    placements: &[Placement],
+    warnings: &[PlacementWarning],
+    max_drift: Duration,
+    format: ReportFormat,
+) -> String {
+    match format {
+        ReportFormat::Text => {
+            let mut lines = vec![];
+            for (index, placement) in placements.iter().enumerate() {
+                let placed_at = match (placement.paragraph_start, placement.drift()) {
+                    (Some(start), Some(drift)) => format!(
+                        "[{}] ({}s)",
+                        format_transcript_time_code(&start),
+                        format_drift(drift)
+                    ),
+                    _ => "end of transcript".to_string(),
+                };
+                let warning_names = warnings_for(index, warnings);
+                let warning_names = if warning_names.is_empty() {
+                    String::new()
+                } else {
+                    format!("  ! {}", warning_names.join(", "))
+                };
+                lines.push(format!(
+                    "{} {} -> {}{}",
+                    placement.entry.time_code, placement.entry.text, placed_at, warning_names
+                ));
+            }
+            lines.join("\n") + "\n"
+        }
+        ReportFormat::Json => {
+            let report = JsonReport {
+                max_drift_seconds: max_drift.as_secs_f64(),
+                entries: placements
+                    .iter()
+                    .enumerate()
+                    .map(|(index, placement)| JsonReportEntry {
+                        text: placement.entry.text.clone(),
+                        time_code: placement.entry.time_code.to_string(),
+                        seconds: placement.entry.time_code.to_seconds(),
+                        paragraph_start: placement
+                            .paragraph_start
+                            .as_ref()
+                            .map(format_transcript_time_code),
+                        drift_seconds: placement.drift(),
+                        warnings: warnings_for(index, warnings),
+                    })
+                    .collect(),
+            };
+            serde_json::to_string_pretty(&report).expect("The report can be written as JSON") + "\n"
+        }
+    }
+}
+
+/// The names of the warnings for the placement at `index`.
+fn warnings_for(index: usize, warnings: &[PlacementWarning]) -> Vec<String> {
+    warnings
+        .iter()
+        .filter(|warning| warning.index() == index)
+        .map(|warning| warning.name().to_string())
+        .collect()
+}
+
+/// Writes a drift in seconds with its sign, like `+7` or `-2.5`.
+fn format_drift(drift: f64) -> String {
+    let drift = (drift * 1000.0).round() / 1000.0;
+    if drift >= 0.0 {
+        format!("+{}", drift)
+    } else {
+        drift.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mark_transcript;
+    use crate::transcript::{DescriptTranscript, TranscriptSource};
+    use common::{OutlineEntry, SlugStyle};
+
+    fn placements() -> (Transcript, Vec<Placement>) {
+        let transcript = DescriptTranscript
+            .parse("[00:00:05] **Rae:** Welcome.\n\n[00:01:37] **Audrow Nash:** Hi.")
+            .expect("The Descript transcript should parse");
+        let outline_entries = vec![
+            OutlineEntry {
+                text: "Introduction".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 1, 0).unwrap(),
+                text: "Background".into(),
+                ..Default::default()
+            },
+            OutlineEntry {
+                time_code: TimeCode::new(0, 2, 0).unwrap(),
+                text: "Wrap up".into(),
+                ..Default::default()
+            },
+        ];
+        let (_, placements) =
+            mark_transcript(&transcript, &outline_entries, None, SlugStyle::GitHub)
+                .expect("Mark transcript should succeed");
+        (transcript, placements)
+    }
+
+    #[test]
+    fn warns_about_drift_and_entries_outside_the_transcript() {
+        let (transcript, placements) = placements();
+
+        let warnings = check_placements(&transcript, &placements, Duration::from_secs(30));
+
+        assert_eq!(
+            warnings
+                .iter()
+                .map(|warning| warning.to_string())
+                .collect::<Vec<String>>(),
+            vec![
+                "Chapter 'Introduction' at 0:00 starts before the first timestamp of the transcript, [00:00:05]",
+                "Chapter 'Background' at 1:00 was placed +37 seconds from its time, more than the 30 allowed",
+                "Chapter 'Wrap up' at 2:00 starts after the last timestamp of the transcript, [00:01:37], so it was added at the end",
+            ]
+        );
+    }
+
+    #[test]
+    fn formats_text_report() {
+        let (transcript, placements) = placements();
+        let warnings = check_placements(&transcript, &placements, Duration::from_secs(30));
+
+        assert_eq!(
+            format_placement_report(
+                &placements,
+                &warnings,
+                Duration::from_secs(30),
+                ReportFormat::Text
+            ),
+            "0:00 Introduction -> [00:00:05] (+5s)  ! before_transcript\n\
+             1:00 Background -> [00:01:37] (+37s)  ! drift\n\
+             2:00 Wrap up -> end of transcript  ! after_transcript\n"
+        );
+    }
+
+    #[test]
+    fn formats_json_report() {
+        let (transcript, placements) = placements();
+        let warnings = check_placements(&transcript, &placements, Duration::from_secs(30));
+
+        let report: serde_json::Value = serde_json::from_str(&format_placement_report(
+            &placements,
+            &warnings,
+            Duration::from_secs(30),
+            ReportFormat::Json,
+        ))
+        .expect("The report should be JSON");
+
+        assert_eq!(report["max_drift_seconds"], 30.0);
+        assert_eq!(report["entries"][1]["paragraph_start"], "00:01:37");
+        assert_eq!(report["entries"][1]["drift_seconds"], 37.0);
+        assert_eq!(report["entries"][1]["warnings"][0], "drift");
+        assert!(report["entries"][2]["drift_seconds"].is_null());
+    }
+
+    #[test]
+    fn reports_warnings_once_for_repeated_entries() {
+        let (transcript, mut placements) = placements();
+        placements.insert(2, placements[1].clone());
+        let warnings = check_placements(&transcript, &placements, Duration::from_secs(30));
+
+        assert_eq!(
+            format_placement_report(
+                &placements,
+                &warnings,
+                Duration::from_secs(30),
+                ReportFormat::Text
+            ),
+            "0:00 Introduction -> [00:00:05] (+5s)  ! before_transcript\n\
+             1:00 Background -> [00:01:37] (+37s)  ! drift\n\
+             1:00 Background -> [00:01:37] (+37s)  ! drift\n\
+             2:00 Wrap up -> end of transcript  ! after_transcript\n"
+        );
+    }
+}
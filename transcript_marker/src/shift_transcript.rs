@@ -0,0 +1,51 @@
+use common::{shift_time_code, EditOperation, TimeCode};
+use regex::Captures;
+
+use crate::error::TranscriptError;
+use crate::transcript::{format_transcript_time_code, transcript_line_regex};
+
+/// Moves every `[HH:MM:SS]` paragraph timestamp to where it is after `edits`.
+pub fn shift_transcript(text: &str, edits: &[EditOperation]) -> Result<String, TranscriptError> {
+    let mut error = None;
+    let shifted = transcript_line_regex().replace_all(text, |captures: &Captures| {
+        let line = &captures[0];
+        let time_code = match captures[1].parse::<TimeCode>() {
+            Ok(time_code) => time_code,
+            Err(e) => {
+                error.get_or_insert(TranscriptError::InvalidTimestamp {
+                    timestamp: captures[1].to_string(),
+                    message: e.to_string(),
+                });
+                return line.to_string();
+            }
+        };
+        let shifted = shift_time_code(&time_code, edits).time_code;
+        line.replacen(&captures[1], &format_transcript_time_code(&shifted), 1)
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(shifted.into_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shifts_paragraph_timestamps() {
+        let transcript = "[00:00:00] **Audrow Nash:** Hello.\n\n[00:03:20] **Guest:** Cut.\n\n[00:12:30] **Guest:** Later.\n";
+        let edits = [
+            "cut 3:10-3:55".parse().unwrap(),
+            "insert 0:45 at 12:30".parse().unwrap(),
+        ];
+
+        let shifted =
+            shift_transcript(transcript, &edits).expect("Shift transcript should succeed");
+
+        assert_eq!(
+            shifted,
+            "[00:00:00] **Audrow Nash:** Hello.\n\n[00:03:10] **Guest:** Cut.\n\n[00:12:30] **Guest:** Later.\n"
+        );
+    }
+}
@@ -0,0 +1,604 @@
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use common::TimeCode;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::error::TranscriptError;
+use crate::parse_captions::{parse_srt, parse_webvtt, starts_with_cue};
+use crate::parse_json_transcripts::{
+    parse_descript_json, parse_otter_json, parse_riverside_json, parse_whisper_json,
+    read_descript_json, read_otter_json, read_riverside_json, read_whisper_json,
+};
+
+/// A paragraph of a transcript.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Paragraph {
+    /// When the paragraph starts, or `None` for a paragraph without a
+    /// timestamp, like the later paragraphs of a long Descript paragraph.
+    pub start: Option<TimeCode>,
+    pub speaker: Option<String>,
+    pub text: String,
+    /// When each word is said, if the transcript has word timings.
+    pub words: Vec<Word>,
+}
+
+/// A word of a transcript and when it's said.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Word {
+    pub start: TimeCode,
+    pub end: TimeCode,
+    pub text: String,
+}
+
+impl Paragraph {
+    /// Writes the paragraph the way Descript exports it, like
+    /// `[00:01:37] **Nag Murty:** Yeah.`
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        if let Some(start) = &self.start {
+            markdown += &format!("[{}] ", format_transcript_time_code(start));
+        }
+        if let Some(speaker) = &self.speaker {
+            markdown += &format!("**{}:** ", speaker);
+        }
+        markdown + &self.text
+    }
+}
+
+/// A transcript as paragraphs, whatever format it was read from.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Transcript {
+    pub paragraphs: Vec<Paragraph>,
+}
+
+/// A pause between segments this long starts a new paragraph.
+const PARAGRAPH_PAUSE: Duration = Duration::from_secs(2);
+/// A paragraph this long is ended at the next end of a sentence.
+const PARAGRAPH_LENGTH: Duration = Duration::from_secs(60);
+
+/// A short stretch of speech, like a caption cue or a Whisper segment, that
+/// is joined with the ones around it into paragraphs.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub(crate) struct Segment {
+    pub start: TimeCode,
+    pub end: TimeCode,
+    pub speaker: Option<String>,
+    pub text: String,
+    pub words: Vec<Word>,
+}
+
+/// Joins consecutive segments into paragraphs.
+///
+/// A new paragraph starts when the speaker changes, after a pause, or at the
+/// end of a sentence once a paragraph has run for a minute.
+pub(crate) fn merge_segments(segments: Vec<Segment>) -> Transcript {
+    let mut paragraphs: Vec<Paragraph> = vec![];
+    let mut last_end = TimeCode::default();
+    for segment in segments
+        .into_iter()
+        .filter(|segment| !segment.text.is_empty())
+    {
+        let continues = paragraphs.last().is_some_and(|paragraph| {
+            let start = paragraph.start.unwrap_or_default();
+            let speaker_changed = segment.speaker.is_some() && segment.speaker != paragraph.speaker;
+            let paused = segment.start.saturating_duration_since(&last_end) >= PARAGRAPH_PAUSE;
+            let long = segment.start.saturating_duration_since(&start) >= PARAGRAPH_LENGTH
+                && paragraph.text.ends_with(['.', '?', '!']);
+            !speaker_changed && !paused && !long
+        });
+        last_end = segment.end;
+        match paragraphs.last_mut() {
+            Some(paragraph) if continues => {
+                paragraph.text += " ";
+                paragraph.text += &segment.text;
+                paragraph.words.extend(segment.words);
+            }
+            _ => {
+                let speaker = segment.speaker.or_else(|| {
+                    paragraphs
+                        .last()
+                        .and_then(|paragraph| paragraph.speaker.clone())
+                });
+                paragraphs.push(Paragraph {
+                    start: Some(segment.start),
+                    speaker,
+                    text: segment.text,
+                    words: segment.words,
+                });
+            }
+        }
+    }
+    Transcript { paragraphs }
+}
+
+/// Something a transcript can be read from, like a Descript export or
+/// captions.
+pub trait TranscriptSource {
+    /// Whether `contents`, read from `path`, look like this source.
+    fn detect(&self, path: &Path, contents: &str) -> bool;
+
+    /// Whether `contents`, already parsed as JSON, look like this source.
+    fn detect_json(&self, _json: &Value) -> bool {
+        false
+    }
+
+    fn parse(&self, contents: &str) -> Result<Transcript, TranscriptError>;
+
+    /// Reads `contents` that have already been parsed as `json`. Sources
+    /// that aren't JSON read the text instead.
+    fn parse_json(&self, contents: &str, _json: Value) -> Result<Transcript, TranscriptError> {
+        self.parse(contents)
+    }
+}
+
+/// The transcript formats that can be read.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TranscriptFormat {
+    /// Descript's text export, with paragraphs like
+    /// `[00:01:37] **Speaker:** text`.
+    Descript,
+    /// SubRip captions.
+    Srt,
+    /// WebVTT captions, with speakers from `<v Speaker>` voice tags.
+    WebVtt,
+    /// Whisper's `verbose_json` output, with segments and word timings.
+    WhisperJson,
+    /// Descript's JSON export, with paragraphs, speakers and word timings.
+    DescriptJson,
+    /// Otter's JSON export, with speaker turns and word timings.
+    OtterJson,
+    /// Riverside's JSON export, with speaker turns and word timings.
+    RiversideJson,
+}
+
+impl TranscriptFormat {
+    /// Every format, in the order they are tried when detecting a format.
+    pub const ALL: [TranscriptFormat; 7] = [
+        TranscriptFormat::WhisperJson,
+        TranscriptFormat::DescriptJson,
+        TranscriptFormat::OtterJson,
+        TranscriptFormat::RiversideJson,
+        TranscriptFormat::WebVtt,
+        TranscriptFormat::Srt,
+        TranscriptFormat::Descript,
+    ];
+
+    /// Picks the format of `contents` read from `path`, from the extension and
+    /// the first line, or for JSON, from the fields it has. Anything
+    /// unrecognized is read as a Descript export.
+    pub fn detect(path: &Path, contents: &str) -> TranscriptFormat {
+        TranscriptFormat::detect_parsed(path, contents, parse_json(contents).as_ref())
+    }
+
+    /// Like `detect`, with `contents` already parsed as `json` if they are
+    /// JSON.
+    fn detect_parsed(path: &Path, contents: &str, json: Option<&Value>) -> TranscriptFormat {
+        TranscriptFormat::ALL
+            .into_iter()
+            .find(|format| {
+                let source = format.source();
+                json.is_some_and(|json| source.detect_json(json)) || source.detect(path, contents)
+            })
+            .unwrap_or(TranscriptFormat::Descript)
+    }
+
+    pub fn source(&self) -> &'static dyn TranscriptSource {
+        match self {
+            TranscriptFormat::Descript => &DescriptTranscript,
+            TranscriptFormat::Srt => &SrtTranscript,
+            TranscriptFormat::WebVtt => &WebVttTranscript,
+            TranscriptFormat::WhisperJson => &WhisperJsonTranscript,
+            TranscriptFormat::DescriptJson => &DescriptJsonTranscript,
+            TranscriptFormat::OtterJson => &OtterJsonTranscript,
+            TranscriptFormat::RiversideJson => &RiversideJsonTranscript,
+        }
+    }
+}
+
+impl FromStr for TranscriptFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<TranscriptFormat, Self::Err> {
+        match text.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "descript" | "text" | "txt" | "md" => Ok(TranscriptFormat::Descript),
+            "srt" | "subrip" => Ok(TranscriptFormat::Srt),
+            "vtt" | "webvtt" => Ok(TranscriptFormat::WebVtt),
+            "whisper" | "whisperjson" => Ok(TranscriptFormat::WhisperJson),
+            "descriptjson" => Ok(TranscriptFormat::DescriptJson),
+            "otter" | "otterjson" => Ok(TranscriptFormat::OtterJson),
+            "riverside" | "riversidejson" => Ok(TranscriptFormat::RiversideJson),
+            _ => Err(format!(
+                "Unknown transcript format '{}', expected descript, srt, vtt, whisper, descript_json, otter or riverside",
+                text
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for TranscriptFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            TranscriptFormat::Descript => "descript",
+            TranscriptFormat::Srt => "srt",
+            TranscriptFormat::WebVtt => "vtt",
+            TranscriptFormat::WhisperJson => "whisper",
+            TranscriptFormat::DescriptJson => "descript_json",
+            TranscriptFormat::OtterJson => "otter",
+            TranscriptFormat::RiversideJson => "riverside",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Reads a transcript, detecting its format from `path` and its contents
+/// unless `format` is given.
+pub fn read_transcript(
+    path: &Path,
+    contents: &str,
+    format: Option<TranscriptFormat>,
+) -> Result<Transcript, TranscriptError> {
+    let json = parse_json(contents);
+    let source = format
+        .unwrap_or_else(|| TranscriptFormat::detect_parsed(path, contents, json.as_ref()))
+        .source();
+    match json {
+        Some(json) => source.parse_json(contents, json),
+        None => source.parse(contents),
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .is_some_and(|extension| extensions.iter().any(|e| extension.eq_ignore_ascii_case(e)))
+}
+
+/// Parses `contents` as JSON, or `None` if they aren't JSON.
+fn parse_json(contents: &str) -> Option<Value> {
+    let contents = contents.trim_start_matches('\u{feff}').trim_start();
+    if !contents.starts_with(['{', '[']) {
+        return None;
+    }
+    serde_json::from_str(contents).ok()
+}
+
+/// Reads a JSON transcript with `read` from its parsed `json`. Parsed JSON
+/// doesn't know where its values were, so if it's invalid, `contents` are
+/// parsed again with `parse` to report the line of the error.
+fn read_json(
+    contents: &str,
+    json: Value,
+    read: fn(Value) -> Result<Transcript, TranscriptError>,
+    parse: fn(&str) -> Result<Transcript, TranscriptError>,
+) -> Result<Transcript, TranscriptError> {
+    read(json).or_else(|_| parse(contents))
+}
+
+/// Whether a JSON transcript's top level object, or the first object of a
+/// top level list, has all of `fields`.
+fn has_json_fields(json: &Value, fields: &[&str]) -> bool {
+    let object = match json {
+        Value::Array(items) => items.first(),
+        value => Some(value),
+    };
+    object
+        .and_then(Value::as_object)
+        .is_some_and(|object| fields.iter().all(|field| object.contains_key(*field)))
+}
+
+/// Matches a transcript paragraph that starts with a `[HH:MM:SS]` timestamp,
+/// capturing the timestamp.
+pub fn transcript_line_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"\[(\d{2,}:\d{2}:\d{2})\] .*").expect("Line regex is valid"))
+}
+
+/// Matches a Descript paragraph, capturing its timestamp, its speaker if it
+/// has one, and its text.
+fn paragraph_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"^\[(\d{2,}:\d{2}:\d{2})\] (?:\*\*(.+?):\*\* )?(.*)$")
+            .expect("Paragraph regex is valid")
+    })
+}
+
+/// Writes a time code as a `HH:MM:SS` transcript timestamp, dropping any
+/// fraction of a second.
+pub(crate) fn format_transcript_time_code(time_code: &TimeCode) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        time_code.hours, time_code.minutes, time_code.seconds
+    )
+}
+
+pub struct DescriptTranscript;
+
+impl TranscriptSource for DescriptTranscript {
+    fn detect(&self, _path: &Path, _contents: &str) -> bool {
+        true
+    }
+
+    /// Reads each line as a paragraph. Lines without a timestamp are kept as
+    /// paragraphs without a start.
+    fn parse(&self, contents: &str) -> Result<Transcript, TranscriptError> {
+        let mut paragraphs = vec![];
+        for line in contents.lines().map(str::trim) {
+            if line.is_empty() {
+                continue;
+            }
+            let paragraph = match paragraph_regex().captures(line) {
+                Some(captures) => Paragraph {
+                    start: Some(captures[1].parse::<TimeCode>().map_err(|e| {
+                        TranscriptError::InvalidTimestamp {
+                            timestamp: captures[1].to_string(),
+                            message: e.to_string(),
+                        }
+                    })?),
+                    speaker: captures.get(2).map(|speaker| speaker.as_str().to_string()),
+                    text: captures[3].to_string(),
+                    ..Default::default()
+                },
+                None => Paragraph {
+                    text: line.to_string(),
+                    ..Default::default()
+                },
+            };
+            paragraphs.push(paragraph);
+        }
+        Ok(Transcript { paragraphs })
+    }
+}
+
+pub struct SrtTranscript;
+
+impl TranscriptSource for SrtTranscript {
+    fn detect(&self, path: &Path, contents: &str) -> bool {
+        has_extension(path, &["srt"]) || starts_with_cue(contents)
+    }
+
+    fn parse(&self, contents: &str) -> Result<Transcript, TranscriptError> {
+        parse_srt(contents)
+    }
+}
+
+pub struct WebVttTranscript;
+
+impl TranscriptSource for WebVttTranscript {
+    fn detect(&self, path: &Path, contents: &str) -> bool {
+        has_extension(path, &["vtt"])
+            || contents
+                .trim_start_matches('\u{feff}')
+                .starts_with("WEBVTT")
+    }
+
+    fn parse(&self, contents: &str) -> Result<Transcript, TranscriptError> {
+        parse_webvtt(contents)
+    }
+}
+
+pub struct WhisperJsonTranscript;
+
+impl TranscriptSource for WhisperJsonTranscript {
+    fn detect(&self, _path: &Path, _contents: &str) -> bool {
+        false
+    }
+
+    fn detect_json(&self, json: &Value) -> bool {
+        has_json_fields(json, &["segments"])
+    }
+
+    fn parse(&self, contents: &str) -> Result<Transcript, TranscriptError> {
+        parse_whisper_json(contents)
+    }
+
+    fn parse_json(&self, contents: &str, json: Value) -> Result<Transcript, TranscriptError> {
+        read_json(contents, json, read_whisper_json, parse_whisper_json)
+    }
+}
+
+pub struct DescriptJsonTranscript;
+
+impl TranscriptSource for DescriptJsonTranscript {
+    fn detect(&self, _path: &Path, _contents: &str) -> bool {
+        false
+    }
+
+    fn detect_json(&self, json: &Value) -> bool {
+        has_json_fields(json, &["paragraphs"])
+    }
+
+    fn parse(&self, contents: &str) -> Result<Transcript, TranscriptError> {
+        parse_descript_json(contents)
+    }
+
+    fn parse_json(&self, contents: &str, json: Value) -> Result<Transcript, TranscriptError> {
+        read_json(contents, json, read_descript_json, parse_descript_json)
+    }
+}
+
+pub struct OtterJsonTranscript;
+
+impl TranscriptSource for OtterJsonTranscript {
+    fn detect(&self, _path: &Path, _contents: &str) -> bool {
+        false
+    }
+
+    fn detect_json(&self, json: &Value) -> bool {
+        has_json_fields(json, &["transcripts"])
+    }
+
+    fn parse(&self, contents: &str) -> Result<Transcript, TranscriptError> {
+        parse_otter_json(contents)
+    }
+
+    fn parse_json(&self, contents: &str, json: Value) -> Result<Transcript, TranscriptError> {
+        read_json(contents, json, read_otter_json, parse_otter_json)
+    }
+}
+
+pub struct RiversideJsonTranscript;
+
+impl TranscriptSource for RiversideJsonTranscript {
+    fn detect(&self, _path: &Path, _contents: &str) -> bool {
+        false
+    }
+
+    fn detect_json(&self, json: &Value) -> bool {
+        json.is_array() && has_json_fields(json, &["speaker", "start"])
+    }
+
+    fn parse(&self, contents: &str) -> Result<Transcript, TranscriptError> {
+        parse_riverside_json(contents)
+    }
+
+    fn parse_json(&self, contents: &str, json: Value) -> Result<Transcript, TranscriptError> {
+        read_json(contents, json, read_riverside_json, parse_riverside_json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_descript_paragraphs() {
+        let transcript = DescriptTranscript
+            .parse("[00:01:37] **Nag Murty:** Yeah. Hi everyone.\n\nAnd then over time.\n[00:03:09] Welcome.\n")
+            .expect("parse should succeed");
+
+        assert_eq!(
+            transcript.paragraphs,
+            vec![
+                Paragraph {
+                    start: Some(TimeCode::new(0, 1, 37).unwrap()),
+                    speaker: Some("Nag Murty".to_string()),
+                    text: "Yeah. Hi everyone.".to_string(),
+                    words: vec![],
+                },
+                Paragraph {
+                    text: "And then over time.".to_string(),
+                    ..Default::default()
+                },
+                Paragraph {
+                    start: Some(TimeCode::new(0, 3, 9).unwrap()),
+                    text: "Welcome.".to_string(),
+                    ..Default::default()
+                },
+            ]
+        );
+        assert_eq!(
+            transcript.paragraphs[0].to_markdown(),
+            "[00:01:37] **Nag Murty:** Yeah. Hi everyone."
+        );
+    }
+
+    #[test]
+    fn ends_long_paragraphs_at_a_sentence() {
+        let segments = (0..8)
+            .map(|index| Segment {
+                start: TimeCode::normalized(0, 0, index * 10),
+                end: TimeCode::normalized(0, 0, index * 10 + 10),
+                speaker: None,
+                text: if index == 5 { "Still" } else { "Going." }.to_string(),
+                words: vec![],
+            })
+            .collect();
+
+        let transcript = merge_segments(segments);
+
+        assert_eq!(
+            transcript
+                .paragraphs
+                .iter()
+                .map(|paragraph| paragraph.start.unwrap().to_string())
+                .collect::<Vec<String>>(),
+            vec!["0:00", "1:10"]
+        );
+    }
+
+    #[test]
+    fn reads_json_transcripts_once_parsed() {
+        let whisper = "{\"segments\": [{\"start\": 1.0, \"end\": 2.0, \"text\": \" Hi.\"}]}";
+
+        for format in [None, Some(TranscriptFormat::WhisperJson)] {
+            let transcript = read_transcript(Path::new("episode.json"), whisper, format)
+                .expect("read_transcript should succeed");
+            assert_eq!(
+                transcript
+                    .paragraphs
+                    .iter()
+                    .map(Paragraph::to_markdown)
+                    .collect::<Vec<String>>(),
+                vec!["[00:00:01] Hi."]
+            );
+        }
+        let transcript = read_transcript(
+            Path::new("episode.json"),
+            whisper,
+            Some(TranscriptFormat::Descript),
+        )
+        .expect("read_transcript should succeed");
+        assert_eq!(transcript.paragraphs[0].text, whisper);
+        assert!(matches!(
+            read_transcript(
+                Path::new("episode.json"),
+                "{\"paragraphs\": 1}",
+                Some(TranscriptFormat::DescriptJson)
+            ),
+            Err(TranscriptError::InvalidJson(message)) if message.ends_with("line 1 column 16")
+        ));
+    }
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(
+            TranscriptFormat::detect(Path::new("episode.srt"), "1\n"),
+            TranscriptFormat::Srt
+        );
+        assert_eq!(
+            TranscriptFormat::detect(Path::new("captions.txt"), "WEBVTT\n\n"),
+            TranscriptFormat::WebVtt
+        );
+        assert_eq!(
+            TranscriptFormat::detect(
+                Path::new("captions.txt"),
+                "\u{feff}1\n00:00:01,000 --> 00:00:02,500\nHi\n"
+            ),
+            TranscriptFormat::Srt
+        );
+        assert_eq!(
+            TranscriptFormat::detect(Path::new("transcript.txt"), "[00:00:00] Hi --> there"),
+            TranscriptFormat::Descript
+        );
+        assert_eq!(
+            TranscriptFormat::detect(Path::new("transcript.md"), "[00:00:00] Hi"),
+            TranscriptFormat::Descript
+        );
+        assert_eq!(
+            TranscriptFormat::detect(
+                Path::new("episode.json"),
+                "{\"text\": \"\", \"segments\": []}"
+            ),
+            TranscriptFormat::WhisperJson
+        );
+        assert_eq!(
+            TranscriptFormat::detect(Path::new("episode.json"), "{\"paragraphs\": []}"),
+            TranscriptFormat::DescriptJson
+        );
+        assert_eq!(
+            TranscriptFormat::detect(Path::new("episode.json"), "{\"transcripts\": []}"),
+            TranscriptFormat::OtterJson
+        );
+        assert_eq!(
+            TranscriptFormat::detect(
+                Path::new("episode.json"),
+                "[{\"speaker\": \"Rae\", \"start\": 0, \"text\": \"Hi\"}]"
+            ),
+            TranscriptFormat::RiversideJson
+        );
+    }
+}